@@ -0,0 +1,158 @@
+extern crate chip8_rust;
+use chip8_rust::controller::{Disassembler, Listing};
+use chip8_rust::defines::{memory_constants::MAX_PROGRAM_SIZE, InstructionSet};
+use chip8_rust::model::SymbolTable;
+use std::{
+    env, fs,
+    io::{self, Write},
+    process, result,
+};
+
+const USAGE: &str = "Usage: chip8-disasm <rom_file> [--set chip8|schip|xochip] \
+                     [--format plain|octo|json] [--symbols <file>]";
+const PLAIN_DATA_BYTES: usize = 4;
+
+#[derive(Copy, Clone, PartialEq)]
+enum Format {
+    Plain,
+    Octo,
+    Json,
+}
+
+struct Options {
+    rom_path: String,
+    instruction_set: InstructionSet,
+    format: Format,
+    symbols_path: Option<String>,
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if let Err(error) = parse_args(&args[1..]).and_then(|options| run(&options)) {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
+
+fn parse_args(args: &[String]) -> result::Result<Options, String> {
+    let mut rom_path: Option<String> = None;
+    let mut options = Options {
+        rom_path: String::new(),
+        instruction_set: InstructionSet::XoChip,
+        format: Format::Plain,
+        symbols_path: None,
+    };
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--set" => {
+                let value = next_value(&mut iter, arg)?;
+                options.instruction_set = InstructionSet::from_name(value)
+                    .ok_or(format!("Error: Unknown instruction set: {}", value))?;
+            }
+            "--format" => {
+                let value = next_value(&mut iter, arg)?;
+                options.format = match value.to_lowercase().as_str() {
+                    "plain" => Format::Plain,
+                    "octo" => Format::Octo,
+                    "json" => Format::Json,
+                    _ => return Err(format!("Error: Unknown output format: {}", value)),
+                };
+            }
+            "--symbols" => {
+                let value = next_value(&mut iter, arg)?;
+                options.symbols_path = Some(value.clone());
+            }
+            _ if arg.starts_with("--") => {
+                return Err(format!("Error: Unknown option: {}\n{}", arg, USAGE));
+            }
+            _ => rom_path = Some(arg.clone()),
+        }
+    }
+
+    options.rom_path = rom_path.ok_or(format!("Error: No ROM file given\n{}", USAGE))?;
+
+    Ok(options)
+}
+
+fn next_value<'a, I>(iter: &mut I, option: &str) -> result::Result<&'a String, String>
+where
+    I: Iterator<Item = &'a String>,
+{
+    iter.next()
+        .ok_or(format!("Error: Missing value for option: {}", option))
+}
+
+fn run(options: &Options) -> result::Result<(), String> {
+    let code = fs::read(&options.rom_path)
+        .map_err(|error| format!("Error: Failed to read {}: {}", options.rom_path, error))?;
+    if code.len() > MAX_PROGRAM_SIZE {
+        return Err(format!(
+            "Error: {} is larger than {} bytes",
+            options.rom_path, MAX_PROGRAM_SIZE
+        ));
+    }
+
+    let symbols = match options.symbols_path.as_ref() {
+        Some(path) => {
+            let text = fs::read_to_string(path)
+                .map_err(|error| format!("Error: Failed to read {}: {}", path, error))?;
+            SymbolTable::parse(&text).map_err(|error| format!("{} in {}", error, path))?
+        }
+        None => SymbolTable::new(),
+    };
+
+    let lines = match options.format {
+        Format::Plain => Listing::analyze(
+            &code,
+            &[],
+            PLAIN_DATA_BYTES,
+            &symbols,
+            options.instruction_set,
+        )
+        .plain_source(),
+        Format::Octo => Disassembler::octo_source(&code, &symbols, options.instruction_set),
+        Format::Json => json_document(
+            &Listing::analyze(
+                &code,
+                &[],
+                PLAIN_DATA_BYTES,
+                &symbols,
+                options.instruction_set,
+            ),
+            options.instruction_set,
+        ),
+    };
+
+    // A closed pipe (e.g. `| head`) just ends the output
+    let stdout = io::stdout();
+    let mut output = stdout.lock();
+    for line in lines.iter() {
+        if writeln!(output, "{}", line).is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn json_document(listing: &Listing, instruction_set: InstructionSet) -> Vec<String> {
+    let entries = listing.json_lines();
+    let mut document = vec![
+        "{".to_string(),
+        format!(
+            "  \"instruction_set\": {},",
+            Listing::json_string(instruction_set.name())
+        ),
+        "  \"lines\": [".to_string(),
+    ];
+    for (index, entry) in entries.iter().enumerate() {
+        let separator = if index + 1 < entries.len() { "," } else { "" };
+        document.push(format!("    {}{}", entry, separator));
+    }
+    document.push("  ]".to_string());
+    document.push("}".to_string());
+
+    document
+}
@@ -0,0 +1,30 @@
+use crate::controller::Token;
+
+use std::fmt;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct AssemblyError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl AssemblyError {
+    pub fn new(token: &Token, message: String) -> AssemblyError {
+        AssemblyError {
+            line: token.line,
+            column: token.column,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for AssemblyError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
@@ -0,0 +1,129 @@
+use crate::controller::{AssemblyError, Token};
+
+use std::{f64::consts, result};
+
+type Result<T> = result::Result<T, AssemblyError>;
+
+const UNARY_OPERATORS: &[&str] = &[
+    "-", "~", "!", "sin", "cos", "tan", "exp", "log", "abs", "sqrt", "sign", "ceil", "floor",
+];
+const BINARY_OPERATORS: &[&str] = &[
+    "-", "+", "*", "/", "%", "&", "|", "^", "<<", ">>", "pow", "min", "max", "<", "<=", "==", "!=",
+    ">=", ">",
+];
+
+// Octo evaluates :calc expressions right to left without operator precedence.
+pub fn evaluate<F>(tokens: &[Token], end: &Token, lookup: &F) -> Result<f64>
+where
+    F: Fn(&Token) -> Result<f64>,
+{
+    let mut position = 0;
+    let value = expression(tokens, &mut position, end, lookup)?;
+    if let Some(token) = tokens.get(position) {
+        return Err(AssemblyError::new(
+            token,
+            format!("Unexpected '{}' in expression", token.text),
+        ));
+    }
+
+    Ok(value)
+}
+
+fn expression<F>(tokens: &[Token], position: &mut usize, end: &Token, lookup: &F) -> Result<f64>
+where
+    F: Fn(&Token) -> Result<f64>,
+{
+    let left = term(tokens, position, end, lookup)?;
+    match tokens.get(*position) {
+        Some(token) if BINARY_OPERATORS.contains(&token.text.as_str()) => {
+            *position += 1;
+            let right = expression(tokens, position, end, lookup)?;
+            if (token.text == "/" || token.text == "%") && right == 0.0 {
+                return Err(AssemblyError::new(token, "Division by zero".to_string()));
+            }
+            Ok(binary(&token.text, left, right))
+        }
+        _ => Ok(left),
+    }
+}
+
+fn term<F>(tokens: &[Token], position: &mut usize, end: &Token, lookup: &F) -> Result<f64>
+where
+    F: Fn(&Token) -> Result<f64>,
+{
+    let token = match tokens.get(*position) {
+        Some(token) => token,
+        None => return Err(AssemblyError::new(end, "Expected a value".to_string())),
+    };
+    *position += 1;
+
+    if token.text == "(" {
+        let value = expression(tokens, position, end, lookup)?;
+        match tokens.get(*position) {
+            Some(close) if close.text == ")" => *position += 1,
+            Some(other) => {
+                return Err(AssemblyError::new(
+                    other,
+                    format!("Expected ')' but found '{}'", other.text),
+                ))
+            }
+            None => return Err(AssemblyError::new(end, "Expected ')'".to_string())),
+        }
+        return Ok(value);
+    }
+    if UNARY_OPERATORS.contains(&token.text.as_str()) {
+        let value = term(tokens, position, end, lookup)?;
+        return Ok(unary(&token.text, value));
+    }
+
+    match token.text.as_str() {
+        "PI" => Ok(consts::PI),
+        "E" => Ok(consts::E),
+        _ => lookup(token),
+    }
+}
+
+fn unary(operator: &str, value: f64) -> f64 {
+    match operator {
+        "-" => -value,
+        "~" => !(value as i64) as f64,
+        "!" => (value == 0.0) as i64 as f64,
+        "sin" => value.sin(),
+        "cos" => value.cos(),
+        "tan" => value.tan(),
+        "exp" => value.exp(),
+        "log" => value.ln(),
+        "abs" => value.abs(),
+        "sqrt" => value.sqrt(),
+        "sign" => value.signum(),
+        "ceil" => value.ceil(),
+        "floor" => value.floor(),
+        _ => value,
+    }
+}
+
+fn binary(operator: &str, left: f64, right: f64) -> f64 {
+    let (left_int, right_int) = (left as i64, right as i64);
+    match operator {
+        "-" => left - right,
+        "+" => left + right,
+        "*" => left * right,
+        "/" => left / right,
+        "%" => left % right,
+        "&" => (left_int & right_int) as f64,
+        "|" => (left_int | right_int) as f64,
+        "^" => (left_int ^ right_int) as f64,
+        "<<" => left_int.wrapping_shl(right_int as u32) as f64,
+        ">>" => left_int.wrapping_shr(right_int as u32) as f64,
+        "pow" => left.powf(right),
+        "min" => left.min(right),
+        "max" => left.max(right),
+        "<" => (left < right) as i64 as f64,
+        "<=" => (left <= right) as i64 as f64,
+        "==" => (left == right) as i64 as f64,
+        "!=" => (left != right) as i64 as f64,
+        ">=" => (left >= right) as i64 as f64,
+        ">" => (left > right) as i64 as f64,
+        _ => left,
+    }
+}
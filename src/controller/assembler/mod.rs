@@ -0,0 +1,8 @@
+mod assembly_error;
+mod calc;
+mod octo_assembler;
+mod tokenizer;
+
+pub use self::assembly_error::AssemblyError;
+pub use self::octo_assembler::{Assembly, OctoAssembler, OCTO_EXTENSION};
+pub use self::tokenizer::{tokenize, Token};
@@ -0,0 +1,1087 @@
+use crate::controller::assembler::calc;
+use crate::controller::{tokenize, AssemblyError, Token};
+use crate::defines::memory_constants::{MEMORYSIZE, PROGRAM_START};
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    result,
+};
+
+pub const OCTO_EXTENSION: &str = "8o";
+
+const MAIN_LABEL: &str = "main";
+const MAX_ADDRESS: usize = 0xFFF;
+const MAX_MACRO_EXPANSIONS: usize = 10000;
+const RESERVED_NAMES: &str = ":= |= &= ^= -= =- += >>= <<= == != < > <= >= key -key hex bighex \
+    random delay : :next :unpack :breakpoint :monitor :alias :const :org :macro :calc :byte :call \
+    ; return clear bcd save load saveflags loadflags buzzer pitch if then begin else end jump \
+    jump0 native sprite loop while again scroll-down scroll-up scroll-right scroll-left lores \
+    hires bighires exit i audio plane long";
+
+type Result<T> = result::Result<T, AssemblyError>;
+
+#[derive(Clone, Default, Debug)]
+pub struct Assembly {
+    pub rom: Vec<u8>,
+    pub labels: BTreeMap<String, usize>,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum FixupKind {
+    Address,
+    LongAddress,
+    Unpack(Option<u8>),
+}
+
+struct Fixup {
+    name: Token,
+    address: usize,
+    kind: FixupKind,
+}
+
+enum Flow {
+    Branch {
+        jump: usize,
+        token: Token,
+    },
+    Loop {
+        start: usize,
+        breaks: Vec<usize>,
+        token: Token,
+    },
+}
+
+struct Macro {
+    arguments: Vec<String>,
+    body: Vec<Token>,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Test {
+    Equal,
+    NotEqual,
+    Less,
+    Greater,
+    LessEqual,
+    GreaterEqual,
+    Key,
+    NotKey,
+}
+
+impl Test {
+    fn negate(self) -> Test {
+        match self {
+            Test::Equal => Test::NotEqual,
+            Test::NotEqual => Test::Equal,
+            Test::Less => Test::GreaterEqual,
+            Test::Greater => Test::LessEqual,
+            Test::LessEqual => Test::Greater,
+            Test::GreaterEqual => Test::Less,
+            Test::Key => Test::NotKey,
+            Test::NotKey => Test::Key,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum TestOperand {
+    Register(usize),
+    Value(u8),
+}
+
+pub struct OctoAssembler {
+    tokens: VecDeque<Token>,
+    last_token: Token,
+    rom: Vec<u8>,
+    here: usize,
+    labels: HashMap<String, usize>,
+    constants: HashMap<String, f64>,
+    aliases: HashMap<String, usize>,
+    macros: HashMap<String, Macro>,
+    fixups: Vec<Fixup>,
+    flow: Vec<Flow>,
+    main_reserved: bool,
+    expansions: usize,
+}
+
+impl OctoAssembler {
+    pub fn assemble(source: &str) -> Result<Assembly> {
+        let mut assembler = OctoAssembler::new(tokenize(source));
+        while let Some(token) = assembler.tokens.pop_front() {
+            assembler.last_token = token.clone();
+            assembler.statement(token)?;
+        }
+
+        assembler.finish()
+    }
+
+    fn new(tokens: Vec<Token>) -> OctoAssembler {
+        OctoAssembler {
+            tokens: tokens.into_iter().collect(),
+            last_token: Token::new("", 1, 1),
+            rom: Vec::new(),
+            here: PROGRAM_START,
+            labels: HashMap::new(),
+            constants: HashMap::new(),
+            aliases: HashMap::new(),
+            macros: HashMap::new(),
+            fixups: Vec::new(),
+            flow: Vec::new(),
+            main_reserved: false,
+            expansions: 0,
+        }
+    }
+
+    fn statement(&mut self, token: Token) -> Result<()> {
+        match token.text.as_str() {
+            ":" => {
+                let name = self.next()?;
+                if name.text != MAIN_LABEL {
+                    self.reserve_main_jump()?;
+                }
+                let address = self.here;
+                self.define_label(name, address)
+            }
+            ":next" => {
+                self.reserve_main_jump()?;
+                let name = self.next()?;
+                let address = self.here + 1;
+                self.define_label(name, address)
+            }
+            ":const" => {
+                let name = self.next()?;
+                let value = self.next()?;
+                let value = self.value(&value)?;
+                self.define_constant(name, value)
+            }
+            ":calc" => {
+                let name = self.next()?;
+                let value = self.calc()?;
+                self.define_constant(name, value)
+            }
+            ":alias" => {
+                let name = self.next()?;
+                self.check_name(&name)?;
+                let register = self.next_register()?;
+                self.aliases.insert(name.text, register);
+                Ok(())
+            }
+            ":macro" => self.define_macro(),
+            ":byte" => {
+                let value = match self.peek() {
+                    Some("{") => {
+                        let value = self.calc()?;
+                        let token = self.last_token.clone();
+                        OctoAssembler::byte_from(&token, value)?
+                    }
+                    _ => self.next_byte()?,
+                };
+                self.emit_byte(value)
+            }
+            ":org" => {
+                self.reserve_main_jump()?;
+                let address = self.next()?;
+                let value = self.value(&address)?;
+                if value < PROGRAM_START as f64 || value >= MEMORYSIZE as f64 {
+                    return Err(AssemblyError::new(
+                        &address,
+                        format!("Address {} is outside of the program memory", address.text),
+                    ));
+                }
+                self.here = value as usize;
+                Ok(())
+            }
+            ":unpack" => self.unpack(),
+            ":call" => {
+                let target = self.next()?;
+                self.emit_address(0x2, target)
+            }
+            ":breakpoint" => self.next().map(|_| ()),
+            ":monitor" => {
+                self.next()?;
+                self.next().map(|_| ())
+            }
+            "return" | ";" => self.emit(0x00EE),
+            "clear" => self.emit(0x00E0),
+            "exit" => self.emit(0x00FD),
+            "lores" => self.emit(0x00FE),
+            "hires" | "bighires" => self.emit(0x00FF),
+            "scroll-right" => self.emit(0x00FB),
+            "scroll-left" => self.emit(0x00FC),
+            "scroll-down" => {
+                let rows = self.next_nibble()?;
+                self.emit(0x00C0 | rows)
+            }
+            "scroll-up" => {
+                let rows = self.next_nibble()?;
+                self.emit(0x00D0 | rows)
+            }
+            "audio" => self.emit(0xF002),
+            "plane" => {
+                let mask = self.next_nibble()?;
+                self.emit(0xF001 | mask << 8)
+            }
+            "jump" => {
+                let target = self.next()?;
+                self.emit_address(0x1, target)
+            }
+            "jump0" => {
+                let target = self.next()?;
+                self.emit_address(0xB, target)
+            }
+            "native" => {
+                let target = self.next()?;
+                self.emit_address(0x0, target)
+            }
+            "sprite" => {
+                let x = self.next_register()?;
+                let y = self.next_register()?;
+                let height = self.next_nibble()?;
+                self.emit(0xD000 | (x as u16) << 8 | (y as u16) << 4 | height)
+            }
+            "save" => self.load_store(0xF055, 0x5002),
+            "load" => self.load_store(0xF065, 0x5003),
+            "saveflags" => self.register_operation(0xF075),
+            "loadflags" => self.register_operation(0xF085),
+            "bcd" => self.register_operation(0xF033),
+            "delay" => self.assign_from_register(0xF015),
+            "buzzer" => self.assign_from_register(0xF018),
+            "pitch" => self.assign_from_register(0xF03A),
+            "i" => self.index_statement(),
+            "if" => self.if_statement(),
+            "else" => self.else_statement(token),
+            "end" => self.end_statement(token),
+            "loop" => {
+                self.flow.push(Flow::Loop {
+                    start: self.here,
+                    breaks: Vec::new(),
+                    token,
+                });
+                Ok(())
+            }
+            "while" => self.while_statement(token),
+            "again" => self.again_statement(token),
+            _ => self.other_statement(token),
+        }
+    }
+
+    fn other_statement(&mut self, token: Token) -> Result<()> {
+        if let Some(register) = self.register(&token) {
+            return self.register_statement(register);
+        }
+        if self.macros.contains_key(&token.text) {
+            return self.expand_macro(token);
+        }
+        if OctoAssembler::parse_number(&token.text).is_some()
+            || self.constants.contains_key(&token.text)
+        {
+            let value = self.value(&token)?;
+            let byte = OctoAssembler::byte_from(&token, value)?;
+            return self.emit_byte(byte);
+        }
+
+        self.emit_address(0x2, token)
+    }
+
+    fn register_statement(&mut self, x: usize) -> Result<()> {
+        let operator = self.next()?;
+        let operand = self.next()?;
+        let x = (x as u16) << 8;
+
+        if let Some(y) = self.register(&operand) {
+            let y = (y as u16) << 4;
+            let code = match operator.text.as_str() {
+                ":=" => 0x0,
+                "|=" => 0x1,
+                "&=" => 0x2,
+                "^=" => 0x3,
+                "+=" => 0x4,
+                "-=" => 0x5,
+                ">>=" => 0x6,
+                "=-" => 0x7,
+                "<<=" => 0xE,
+                _ => return Err(OctoAssembler::unexpected(&operator)),
+            };
+            return self.emit(0x8000 | x | y | code);
+        }
+
+        match (operator.text.as_str(), operand.text.as_str()) {
+            (":=", "key") => self.emit(0xF00A | x),
+            (":=", "delay") => self.emit(0xF007 | x),
+            (":=", "random") => {
+                let mask = self.next_byte()?;
+                self.emit(0xC000 | x | mask as u16)
+            }
+            (":=", _) => {
+                let value = self.byte(&operand)?;
+                self.emit(0x6000 | x | value as u16)
+            }
+            ("+=", _) => {
+                let value = self.byte(&operand)?;
+                self.emit(0x7000 | x | value as u16)
+            }
+            ("-=", _) => {
+                let value = self.byte(&operand)?;
+                self.emit(0x7000 | x | value.wrapping_neg() as u16)
+            }
+            _ => Err(OctoAssembler::unexpected(&operator)),
+        }
+    }
+
+    fn index_statement(&mut self) -> Result<()> {
+        let operator = self.next()?;
+        match operator.text.as_str() {
+            ":=" => {
+                let operand = self.next()?;
+                match operand.text.as_str() {
+                    "hex" => self.register_operation(0xF029),
+                    "bighex" => self.register_operation(0xF030),
+                    "long" => {
+                        let target = self.next()?;
+                        self.emit(0xF000)?;
+                        self.emit_long_address(target)
+                    }
+                    _ => self.emit_address(0xA, operand),
+                }
+            }
+            "+=" => self.register_operation(0xF01E),
+            _ => Err(OctoAssembler::unexpected(&operator)),
+        }
+    }
+
+    fn load_store(&mut self, single: u16, range: u16) -> Result<()> {
+        let x = self.next_register()?;
+        if self.peek() != Some("-") {
+            return self.emit(single | (x as u16) << 8);
+        }
+        self.next()?;
+        let y = self.next_register()?;
+
+        self.emit(range | (x as u16) << 8 | (y as u16) << 4)
+    }
+
+    fn register_operation(&mut self, opcode: u16) -> Result<()> {
+        let x = self.next_register()?;
+        self.emit(opcode | (x as u16) << 8)
+    }
+
+    fn assign_from_register(&mut self, opcode: u16) -> Result<()> {
+        let operator = self.next()?;
+        if operator.text != ":=" {
+            return Err(OctoAssembler::unexpected(&operator));
+        }
+        self.register_operation(opcode)
+    }
+
+    fn if_statement(&mut self) -> Result<()> {
+        let (x, test, operand) = self.condition()?;
+        let keyword = self.next()?;
+        match keyword.text.as_str() {
+            "then" => self.emit_skip_unless(x, test, operand),
+            "begin" => {
+                self.emit_skip_unless(x, test.negate(), operand)?;
+                let jump = self.here;
+                self.emit(0x1000)?;
+                self.flow.push(Flow::Branch {
+                    jump,
+                    token: keyword,
+                });
+                Ok(())
+            }
+            _ => Err(AssemblyError::new(
+                &keyword,
+                format!("Expected 'then' or 'begin' but found '{}'", keyword.text),
+            )),
+        }
+    }
+
+    fn else_statement(&mut self, token: Token) -> Result<()> {
+        match self.flow.pop() {
+            Some(Flow::Branch { jump, .. }) => {
+                let else_jump = self.here;
+                self.emit(0x1000)?;
+                let here = self.here;
+                self.patch_jump(jump, here, &token)?;
+                self.flow.push(Flow::Branch {
+                    jump: else_jump,
+                    token,
+                });
+                Ok(())
+            }
+            _ => Err(AssemblyError::new(
+                &token,
+                "'else' without 'begin'".to_string(),
+            )),
+        }
+    }
+
+    fn end_statement(&mut self, token: Token) -> Result<()> {
+        match self.flow.pop() {
+            Some(Flow::Branch { jump, .. }) => {
+                let here = self.here;
+                self.patch_jump(jump, here, &token)
+            }
+            _ => Err(AssemblyError::new(
+                &token,
+                "'end' without 'begin'".to_string(),
+            )),
+        }
+    }
+
+    fn while_statement(&mut self, token: Token) -> Result<()> {
+        let (x, test, operand) = self.condition()?;
+        self.emit_skip_unless(x, test.negate(), operand)?;
+        let jump = self.here;
+        self.emit(0x1000)?;
+        for flow in self.flow.iter_mut().rev() {
+            if let Flow::Loop { breaks, .. } = flow {
+                breaks.push(jump);
+                return Ok(());
+            }
+        }
+
+        Err(AssemblyError::new(
+            &token,
+            "'while' outside of a loop".to_string(),
+        ))
+    }
+
+    fn again_statement(&mut self, token: Token) -> Result<()> {
+        match self.flow.pop() {
+            Some(Flow::Loop { start, breaks, .. }) => {
+                let jump = self.here;
+                self.emit(0x1000)?;
+                self.patch_jump(jump, start, &token)?;
+                let here = self.here;
+                for address in breaks {
+                    self.patch_jump(address, here, &token)?;
+                }
+                Ok(())
+            }
+            _ => Err(AssemblyError::new(
+                &token,
+                "'again' without 'loop'".to_string(),
+            )),
+        }
+    }
+
+    fn condition(&mut self) -> Result<(usize, Test, TestOperand)> {
+        let x = self.next_register()?;
+        let operator = self.next()?;
+        let test = match operator.text.as_str() {
+            "key" => return Ok((x, Test::Key, TestOperand::Register(x))),
+            "-key" => return Ok((x, Test::NotKey, TestOperand::Register(x))),
+            "==" => Test::Equal,
+            "!=" => Test::NotEqual,
+            "<" => Test::Less,
+            ">" => Test::Greater,
+            "<=" => Test::LessEqual,
+            ">=" => Test::GreaterEqual,
+            _ => return Err(OctoAssembler::unexpected(&operator)),
+        };
+        let operand = self.next()?;
+        let operand = match self.register(&operand) {
+            Some(y) => TestOperand::Register(y),
+            None => TestOperand::Value(self.byte(&operand)?),
+        };
+
+        Ok((x, test, operand))
+    }
+
+    // Emits the instructions which skip the next instruction unless the condition holds.
+    fn emit_skip_unless(&mut self, x: usize, test: Test, operand: TestOperand) -> Result<()> {
+        let left = (x as u16) << 8;
+        match (test, operand) {
+            (Test::Equal, TestOperand::Register(y)) => self.emit(0x9000 | left | (y as u16) << 4),
+            (Test::Equal, TestOperand::Value(value)) => self.emit(0x4000 | left | value as u16),
+            (Test::NotEqual, TestOperand::Register(y)) => {
+                self.emit(0x5000 | left | (y as u16) << 4)
+            }
+            (Test::NotEqual, TestOperand::Value(value)) => self.emit(0x3000 | left | value as u16),
+            (Test::Key, _) => self.emit(0xE0A1 | left),
+            (Test::NotKey, _) => self.emit(0xE09E | left),
+            (Test::Less, _) | (Test::GreaterEqual, _) => {
+                self.emit_greater_equal_flag(TestOperand::Register(x), operand)?;
+                self.emit(if test == Test::Less { 0x3F01 } else { 0x3F00 })
+            }
+            (Test::Greater, _) | (Test::LessEqual, _) => {
+                self.emit_greater_equal_flag(operand, TestOperand::Register(x))?;
+                self.emit(if test == Test::Greater {
+                    0x3F01
+                } else {
+                    0x3F00
+                })
+            }
+        }
+    }
+
+    // Sets vF to 1 if left >= right, one of both operands is always a register.
+    fn emit_greater_equal_flag(&mut self, left: TestOperand, right: TestOperand) -> Result<()> {
+        match (left, right) {
+            (TestOperand::Register(x), TestOperand::Register(y)) => {
+                self.emit(0x8F00 | (x as u16) << 4)?;
+                self.emit(0x8F05 | (y as u16) << 4)
+            }
+            (TestOperand::Register(x), TestOperand::Value(value)) => {
+                self.emit(0x6F00 | value as u16)?;
+                self.emit(0x8F07 | (x as u16) << 4)
+            }
+            (TestOperand::Value(value), TestOperand::Register(y)) => {
+                self.emit(0x6F00 | value as u16)?;
+                self.emit(0x8F05 | (y as u16) << 4)
+            }
+            (TestOperand::Value(_), TestOperand::Value(_)) => Ok(()),
+        }
+    }
+
+    fn unpack(&mut self) -> Result<()> {
+        let prefix = self.next()?;
+        let nibble = match prefix.text.as_str() {
+            "long" => None,
+            _ => Some(self.nibble(&prefix)? as u8),
+        };
+        let target = self.next()?;
+        let address = self.here;
+        let (high, low) = match self.address_value(&target)? {
+            Some(value) => OctoAssembler::unpack_bytes(&target, value, nibble)?,
+            None => {
+                self.fixups.push(Fixup {
+                    name: target,
+                    address,
+                    kind: FixupKind::Unpack(nibble),
+                });
+                (0, 0)
+            }
+        };
+        self.emit(0x6000 | high as u16)?;
+        self.emit(0x6100 | low as u16)
+    }
+
+    fn unpack_bytes(token: &Token, value: usize, nibble: Option<u8>) -> Result<(u8, u8)> {
+        match nibble {
+            Some(nibble) => {
+                OctoAssembler::check_address(token, value, MAX_ADDRESS)?;
+                Ok((nibble << 4 | (value >> 8) as u8, value as u8))
+            }
+            None => {
+                OctoAssembler::check_address(token, value, MEMORYSIZE - 1)?;
+                Ok(((value >> 8) as u8, value as u8))
+            }
+        }
+    }
+
+    fn define_label(&mut self, name: Token, address: usize) -> Result<()> {
+        self.check_name(&name)?;
+        self.labels.insert(name.text, address);
+        Ok(())
+    }
+
+    fn define_constant(&mut self, name: Token, value: f64) -> Result<()> {
+        self.check_name(&name)?;
+        self.constants.insert(name.text, value);
+        Ok(())
+    }
+
+    fn define_macro(&mut self) -> Result<()> {
+        let name = self.next()?;
+        self.check_name(&name)?;
+        let mut arguments: Vec<String> = Vec::new();
+        loop {
+            let argument = self.next()?;
+            if argument.text == "{" {
+                break;
+            }
+            arguments.push(argument.text);
+        }
+        let body = self.block()?;
+        self.macros.insert(name.text, Macro { arguments, body });
+
+        Ok(())
+    }
+
+    fn expand_macro(&mut self, name: Token) -> Result<()> {
+        self.expansions += 1;
+        if self.expansions > MAX_MACRO_EXPANSIONS {
+            return Err(AssemblyError::new(
+                &name,
+                format!("Too many expansions of macro '{}'", name.text),
+            ));
+        }
+        let argument_count = self.macros[&name.text].arguments.len();
+        let mut values: HashMap<String, String> = HashMap::new();
+        for index in 0..argument_count {
+            let value = self.next()?;
+            values.insert(self.macros[&name.text].arguments[index].clone(), value.text);
+        }
+        let body = &self.macros[&name.text].body;
+        let expanded: Vec<Token> = body
+            .iter()
+            .map(|token| match values.get(&token.text) {
+                Some(value) => Token::new(value, token.line, token.column),
+                None => token.clone(),
+            })
+            .collect();
+        for token in expanded.into_iter().rev() {
+            self.tokens.push_front(token);
+        }
+
+        Ok(())
+    }
+
+    fn calc(&mut self) -> Result<f64> {
+        let open = self.next()?;
+        if open.text != "{" {
+            return Err(AssemblyError::new(
+                &open,
+                format!("Expected '{{' but found '{}'", open.text),
+            ));
+        }
+        let body = self.block()?;
+        let end = self.last_token.clone();
+
+        calc::evaluate(&body, &end, &|token| self.value(token))
+    }
+
+    fn block(&mut self) -> Result<Vec<Token>> {
+        let mut body: Vec<Token> = Vec::new();
+        let mut depth = 0;
+        loop {
+            let token = self.next()?;
+            match token.text.as_str() {
+                "{" => depth += 1,
+                "}" if depth == 0 => return Ok(body),
+                "}" => depth -= 1,
+                _ => {}
+            }
+            body.push(token);
+        }
+    }
+
+    fn emit_address(&mut self, high_nibble: u16, target: Token) -> Result<()> {
+        let address = self.here;
+        match self.address_value(&target)? {
+            Some(value) => {
+                OctoAssembler::check_address(&target, value, MAX_ADDRESS)?;
+                self.emit(high_nibble << 12 | value as u16)
+            }
+            None => {
+                self.fixups.push(Fixup {
+                    name: target,
+                    address,
+                    kind: FixupKind::Address,
+                });
+                self.emit(high_nibble << 12)
+            }
+        }
+    }
+
+    fn emit_long_address(&mut self, target: Token) -> Result<()> {
+        let address = self.here;
+        match self.address_value(&target)? {
+            Some(value) => {
+                OctoAssembler::check_address(&target, value, MEMORYSIZE - 1)?;
+                self.emit(value as u16)
+            }
+            None => {
+                self.fixups.push(Fixup {
+                    name: target,
+                    address,
+                    kind: FixupKind::LongAddress,
+                });
+                self.emit(0)
+            }
+        }
+    }
+
+    fn reserve_main_jump(&mut self) -> Result<()> {
+        if self.main_reserved {
+            return Ok(());
+        }
+        self.main_reserved = true;
+        if !self.labels.contains_key(MAIN_LABEL) {
+            self.emit_address(0x1, Token::new(MAIN_LABEL, 1, 1))?;
+        }
+
+        Ok(())
+    }
+
+    fn emit(&mut self, opcode: u16) -> Result<()> {
+        self.emit_byte((opcode >> 8) as u8)?;
+        self.emit_byte(opcode as u8)
+    }
+
+    fn emit_byte(&mut self, byte: u8) -> Result<()> {
+        self.reserve_main_jump()?;
+        if self.here >= MEMORYSIZE {
+            return Err(AssemblyError::new(
+                &self.last_token,
+                "Program does not fit into memory".to_string(),
+            ));
+        }
+        let index = self.here - PROGRAM_START;
+        if self.rom.len() <= index {
+            self.rom.resize(index + 1, 0);
+        }
+        self.rom[index] = byte;
+        self.here += 1;
+
+        Ok(())
+    }
+
+    fn patch_jump(&mut self, address: usize, target: usize, token: &Token) -> Result<()> {
+        OctoAssembler::check_address(token, target, MAX_ADDRESS)?;
+        let index = address - PROGRAM_START;
+        self.rom[index] = (self.rom[index] & 0xF0) | (target >> 8) as u8;
+        self.rom[index + 1] = target as u8;
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Assembly> {
+        if let Some(flow) = self.flow.last() {
+            return Err(match flow {
+                Flow::Branch { token, .. } => {
+                    AssemblyError::new(token, format!("'{}' without 'end'", token.text))
+                }
+                Flow::Loop { token, .. } => {
+                    AssemblyError::new(token, "'loop' without 'again'".to_string())
+                }
+            });
+        }
+
+        for fixup in self.fixups.iter() {
+            let target = match self.labels.get(&fixup.name.text) {
+                Some(target) => *target,
+                None if fixup.name.text == MAIN_LABEL => {
+                    return Err(AssemblyError::new(
+                        &fixup.name,
+                        "Program has no ': main' label".to_string(),
+                    ))
+                }
+                None => {
+                    return Err(AssemblyError::new(
+                        &fixup.name,
+                        format!("Undefined name '{}'", fixup.name.text),
+                    ))
+                }
+            };
+            let index = fixup.address - PROGRAM_START;
+            match fixup.kind {
+                FixupKind::Address => {
+                    OctoAssembler::check_address(&fixup.name, target, MAX_ADDRESS)?;
+                    self.rom[index] |= (target >> 8) as u8;
+                    self.rom[index + 1] = target as u8;
+                }
+                FixupKind::LongAddress => {
+                    self.rom[index] = (target >> 8) as u8;
+                    self.rom[index + 1] = target as u8;
+                }
+                FixupKind::Unpack(nibble) => {
+                    let (high, low) = OctoAssembler::unpack_bytes(&fixup.name, target, nibble)?;
+                    self.rom[index + 1] = high;
+                    self.rom[index + 3] = low;
+                }
+            }
+        }
+
+        Ok(Assembly {
+            rom: self.rom,
+            labels: self.labels.into_iter().collect(),
+        })
+    }
+
+    fn next(&mut self) -> Result<Token> {
+        match self.tokens.pop_front() {
+            Some(token) => {
+                self.last_token = token.clone();
+                Ok(token)
+            }
+            None => Err(AssemblyError::new(
+                &self.last_token,
+                "Unexpected end of file".to_string(),
+            )),
+        }
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens.front().map(|token| token.text.as_str())
+    }
+
+    fn next_register(&mut self) -> Result<usize> {
+        let token = self.next()?;
+        self.register(&token).ok_or_else(|| {
+            AssemblyError::new(
+                &token,
+                format!("Expected a register but found '{}'", token.text),
+            )
+        })
+    }
+
+    fn next_byte(&mut self) -> Result<u8> {
+        let token = self.next()?;
+        self.byte(&token)
+    }
+
+    fn next_nibble(&mut self) -> Result<u16> {
+        let token = self.next()?;
+        self.nibble(&token)
+    }
+
+    fn register(&self, token: &Token) -> Option<usize> {
+        if let Some(register) = self.aliases.get(&token.text) {
+            return Some(*register);
+        }
+        let mut characters = token.text.chars();
+        match (characters.next(), characters.next(), characters.next()) {
+            (Some('v'), Some(digit), None) | (Some('V'), Some(digit), None) => {
+                digit.to_digit(16).map(|digit| digit as usize)
+            }
+            _ => None,
+        }
+    }
+
+    fn value(&self, token: &Token) -> Result<f64> {
+        if let Some(value) = OctoAssembler::parse_number(&token.text) {
+            return Ok(value);
+        }
+        if let Some(value) = self.constants.get(&token.text) {
+            return Ok(*value);
+        }
+        if let Some(address) = self.labels.get(&token.text) {
+            return Ok(*address as f64);
+        }
+        if token.text == "HERE" {
+            return Ok(self.here as f64);
+        }
+
+        Err(AssemblyError::new(
+            token,
+            format!("Undefined name '{}'", token.text),
+        ))
+    }
+
+    fn byte(&self, token: &Token) -> Result<u8> {
+        let value = self.value(token)?;
+        OctoAssembler::byte_from(token, value)
+    }
+
+    fn byte_from(token: &Token, value: f64) -> Result<u8> {
+        let value = value.floor() as i64;
+        if !(-128..=255).contains(&value) {
+            return Err(AssemblyError::new(
+                token,
+                format!("Value {} does not fit into a byte", value),
+            ));
+        }
+
+        Ok(value as u8)
+    }
+
+    fn nibble(&self, token: &Token) -> Result<u16> {
+        let value = self.value(token)?.floor() as i64;
+        if !(0..=0xF).contains(&value) {
+            return Err(AssemblyError::new(
+                token,
+                format!("Value {} does not fit into a nibble", value),
+            ));
+        }
+
+        Ok(value as u16)
+    }
+
+    fn address_value(&self, token: &Token) -> Result<Option<usize>> {
+        if OctoAssembler::parse_number(&token.text).is_some()
+            || self.constants.contains_key(&token.text)
+            || self.labels.contains_key(&token.text)
+        {
+            let value = self.value(token)?.floor();
+            if value < 0.0 {
+                return Err(AssemblyError::new(
+                    token,
+                    format!("Address {} is negative", token.text),
+                ));
+            }
+            return Ok(Some(value as usize));
+        }
+        self.check_name(token)?;
+
+        Ok(None)
+    }
+
+    fn check_address(token: &Token, address: usize, maximum: usize) -> Result<()> {
+        if address > maximum {
+            return Err(AssemblyError::new(
+                token,
+                format!(
+                    "Address 0x{:X} of '{}' is out of range",
+                    address, token.text
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn check_name(&self, token: &Token) -> Result<()> {
+        let message = if RESERVED_NAMES
+            .split_whitespace()
+            .any(|name| name == token.text)
+        {
+            format!("'{}' is a reserved name", token.text)
+        } else if self.register(token).is_some() {
+            format!("'{}' is a register", token.text)
+        } else if OctoAssembler::parse_number(&token.text).is_some() {
+            format!("'{}' is a number", token.text)
+        } else if self.labels.contains_key(&token.text) || self.constants.contains_key(&token.text)
+        {
+            format!("'{}' is already defined", token.text)
+        } else {
+            return Ok(());
+        };
+
+        Err(AssemblyError::new(token, message))
+    }
+
+    fn parse_number(text: &str) -> Option<f64> {
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, text),
+        };
+        let value = if let Some(hex) = digits.strip_prefix("0x") {
+            i64::from_str_radix(hex, 16).ok()? as f64
+        } else if let Some(binary) = digits.strip_prefix("0b") {
+            i64::from_str_radix(binary, 2).ok()? as f64
+        } else if digits.starts_with(|character: char| character.is_ascii_digit()) {
+            digits.parse::<f64>().ok()?
+        } else {
+            return None;
+        };
+
+        Some(if negative { -value } else { value })
+    }
+
+    fn unexpected(token: &Token) -> AssemblyError {
+        AssemblyError::new(token, format!("Unexpected '{}'", token.text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::Disassembler;
+    use crate::defines::InstructionSet;
+    use crate::model::SymbolTable;
+
+    use std::{fs, path::Path};
+
+    fn rom(source: &str) -> Vec<u8> {
+        OctoAssembler::assemble(&format!(": main\n{}", source))
+            .unwrap()
+            .rom
+    }
+
+    fn error(source: &str) -> (usize, usize) {
+        let error = OctoAssembler::assemble(source).unwrap_err();
+        (error.line, error.column)
+    }
+
+    #[test]
+    fn calc_evaluates_right_to_left() {
+        assert_eq!(rom(":calc foo { 3 * 4 + 1 }\nv0 := foo"), [0x60, 0x0F]);
+        assert_eq!(
+            rom(":const base 8\n:calc foo { base * 2 + base / 4 }\nv0 := foo"),
+            [0x60, 0x20]
+        );
+    }
+
+    #[test]
+    fn macros_substitute_their_arguments() {
+        assert_eq!(
+            rom(":macro setv reg val { reg := val }\nsetv v1 5\nsetv v2 0x20"),
+            [0x61, 0x05, 0x62, 0x20]
+        );
+    }
+
+    #[test]
+    fn if_then_skips_the_statement() {
+        assert_eq!(rom("if v0 == 1 then v1 := 2"), [0x40, 0x01, 0x61, 0x02]);
+    }
+
+    #[test]
+    fn if_begin_else_end_jumps_around_the_branches() {
+        assert_eq!(
+            rom("if v0 == 1 begin v1 := 2 else v1 := 3 end"),
+            [0x30, 0x01, 0x12, 0x08, 0x61, 0x02, 0x12, 0x0A, 0x61, 0x03]
+        );
+    }
+
+    #[test]
+    fn loop_while_again_jumps_out_and_back() {
+        assert_eq!(
+            rom("loop v0 += 1 while v0 != 5 again"),
+            [0x70, 0x01, 0x40, 0x05, 0x12, 0x08, 0x12, 0x00]
+        );
+    }
+
+    #[test]
+    fn unpack_loads_a_label_into_two_registers() {
+        let assembly = OctoAssembler::assemble(": main\n:unpack 0xA data\n: data 0x12").unwrap();
+
+        assert_eq!(assembly.rom, [0x60, 0xA2, 0x61, 0x04, 0x12]);
+        assert_eq!(assembly.labels["data"], 0x204);
+    }
+
+    #[test]
+    fn errors_point_at_line_and_column() {
+        assert_eq!(error("v0 := 1\n  v1 += bogus"), (2, 9));
+        assert_eq!(error(": main\n\n   jump nowhere"), (3, 9));
+        assert_eq!(error("v0 := 300"), (1, 7));
+        assert_eq!(error(": main\nloop v0 += 1"), (2, 1));
+    }
+
+    #[test]
+    fn calc_reports_division_by_zero_at_the_operator() {
+        let error = OctoAssembler::assemble(":calc foo { 1 +\n  8 / 0 }").unwrap_err();
+
+        assert_eq!((error.line, error.column), (2, 5));
+        assert_eq!(error.message, "Division by zero");
+    }
+
+    #[test]
+    fn out_of_range_addresses_are_shown_in_hex() {
+        let error = OctoAssembler::assemble(": main\njump 0x1000").unwrap_err();
+
+        assert_eq!(error.message, "Address 0x1000 of '0x1000' is out of range");
+    }
+
+    #[test]
+    fn disassembled_roms_reassemble_to_the_same_bytes() {
+        let games = Path::new(env!("CARGO_MANIFEST_DIR")).join("Games");
+        let mut checked = 0;
+        for directory in [games.clone(), games.join("Super")].iter() {
+            for entry in fs::read_dir(directory).unwrap() {
+                let path = entry.unwrap().path();
+                if !path.is_file() {
+                    continue;
+                }
+                let code = fs::read(&path).unwrap();
+                for set in [
+                    InstructionSet::Chip8,
+                    InstructionSet::SChip,
+                    InstructionSet::XoChip,
+                ]
+                .iter()
+                {
+                    let source =
+                        Disassembler::octo_source(&code, &SymbolTable::new(), *set).join("\n");
+                    let assembly = OctoAssembler::assemble(&source)
+                        .unwrap_or_else(|error| panic!("{:?} {:?}: {}", path, set, error));
+                    assert!(assembly.rom == code, "{:?} {:?}", path, set);
+                }
+                checked += 1;
+            }
+        }
+
+        assert!(checked > 0);
+    }
+}
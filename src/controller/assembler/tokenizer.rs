@@ -0,0 +1,46 @@
+#[derive(Clone, PartialEq, Debug)]
+pub struct Token {
+    pub text: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Token {
+    pub fn new(text: &str, line: usize, column: usize) -> Token {
+        Token {
+            text: text.to_string(),
+            line,
+            column,
+        }
+    }
+}
+
+pub fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+
+    for (line_index, line) in source.lines().enumerate() {
+        let mut start: Option<usize> = None;
+        let mut text = String::new();
+        for (column, character) in line.chars().enumerate() {
+            if character == '#' && start.is_none() {
+                break;
+            }
+            if character.is_whitespace() {
+                if let Some(column) = start.take() {
+                    tokens.push(Token::new(&text, line_index + 1, column + 1));
+                    text.clear();
+                }
+                continue;
+            }
+            if start.is_none() {
+                start = Some(column);
+            }
+            text.push(character);
+        }
+        if let Some(column) = start {
+            tokens.push(Token::new(&text, line_index + 1, column + 1));
+        }
+    }
+
+    tokens
+}
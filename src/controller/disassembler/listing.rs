@@ -0,0 +1,335 @@
+use crate::controller::{Disassembler, LONG_LOAD_OPCODE};
+use crate::defines::{memory_constants::PROGRAM_START, InstructionSet};
+use crate::model::SymbolTable;
+
+use std::collections::BTreeMap;
+
+const MAIN_LABEL: &str = "main";
+
+#[derive(Copy, Clone, PartialEq, PartialOrd, Debug)]
+enum LabelKind {
+    Data,
+    Jump,
+    Subroutine,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum LineKind {
+    Code(u16),
+    LongLoad(u16),
+    Data,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ListingLine {
+    pub address: usize,
+    pub bytes: Vec<u8>,
+    pub kind: LineKind,
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct Listing {
+    pub lines: Vec<ListingLine>,
+    pub labels: BTreeMap<usize, String>,
+    symbols: SymbolTable,
+}
+
+impl Listing {
+    // Follows jumps, calls and skips from the entry points, everything that is
+    // never reached is split into data lines of up to `data_bytes` bytes.
+    // Symbols replace the generated label names and start a new data line,
+    // opcodes outside of the instruction set are never treated as code.
+    pub fn analyze(
+        code: &[u8],
+        entry_points: &[usize],
+        data_bytes: usize,
+        symbols: &SymbolTable,
+        instruction_set: InstructionSet,
+    ) -> Listing {
+        let mut lengths: Vec<usize> = vec![0; code.len()];
+        let mut covered: Vec<bool> = vec![false; code.len()];
+        let mut targets: BTreeMap<usize, LabelKind> = BTreeMap::new();
+        let mut pending: Vec<usize> = entry_points.to_vec();
+        pending.push(PROGRAM_START);
+
+        while let Some(address) = pending.pop() {
+            let opcode = match Listing::read_opcode(code, address) {
+                Some(opcode) => opcode,
+                None => continue,
+            };
+            let index = address - PROGRAM_START;
+            let length = if opcode == LONG_LOAD_OPCODE { 4 } else { 2 };
+            if index + length > code.len()
+                || covered[index..index + length].iter().any(|byte| *byte)
+                || Disassembler::disassemble(&opcode) == "Unknown"
+                || !instruction_set.supports(opcode)
+            {
+                continue;
+            }
+            lengths[index] = length;
+            covered[index..index + length]
+                .iter_mut()
+                .for_each(|byte| *byte = true);
+
+            let next = address + length;
+            let target = (opcode & 0x0FFF) as usize;
+            match opcode >> 12 {
+                0x0 if opcode == 0x00EE || opcode == 0x00FD => {}
+                0x5 if opcode & 0x000F != 0 => pending.push(next),
+                0x1 | 0xB => {
+                    Listing::add_target(&mut targets, target, LabelKind::Jump);
+                    pending.push(target);
+                }
+                0x2 => {
+                    Listing::add_target(&mut targets, target, LabelKind::Subroutine);
+                    pending.push(target);
+                    pending.push(next);
+                }
+                0x3 | 0x4 | 0x5 | 0x9 | 0xE => {
+                    let skipped = match Listing::read_opcode(code, next) {
+                        Some(LONG_LOAD_OPCODE) if instruction_set.supports(LONG_LOAD_OPCODE) => 4,
+                        _ => 2,
+                    };
+                    pending.push(next);
+                    pending.push(next + skipped);
+                }
+                0xA => {
+                    Listing::add_target(&mut targets, target, LabelKind::Data);
+                    pending.push(next);
+                }
+                _ if opcode == LONG_LOAD_OPCODE => {
+                    let target = (code[index + 2] as usize) << 8 | code[index + 3] as usize;
+                    Listing::add_target(&mut targets, target, LabelKind::Data);
+                    pending.push(next);
+                }
+                _ => pending.push(next),
+            }
+        }
+
+        let mut lines: Vec<ListingLine> = Vec::new();
+        let mut index = 0;
+        while index < code.len() {
+            let address = PROGRAM_START + index;
+            let length = lengths[index];
+            if length > 0 {
+                let opcode = (code[index] as u16) << 8 | code[index + 1] as u16;
+                let kind = match opcode {
+                    LONG_LOAD_OPCODE => {
+                        LineKind::LongLoad((code[index + 2] as u16) << 8 | code[index + 3] as u16)
+                    }
+                    _ => LineKind::Code(opcode),
+                };
+                lines.push(ListingLine {
+                    address,
+                    bytes: code[index..index + length].to_vec(),
+                    kind,
+                });
+                index += length;
+                continue;
+            }
+
+            let mut end = index + 1;
+            while end < code.len()
+                && end - index < data_bytes
+                && !covered[end]
+                && !targets.contains_key(&(PROGRAM_START + end))
+                && symbols.name(PROGRAM_START + end).is_none()
+            {
+                end += 1;
+            }
+            lines.push(ListingLine {
+                address,
+                bytes: code[index..end].to_vec(),
+                kind: LineKind::Data,
+            });
+            index = end;
+        }
+
+        let mut labels: BTreeMap<usize, String> = BTreeMap::new();
+        for line in lines.iter() {
+            let symbol = symbols
+                .name(line.address)
+                .filter(|name| *name != MAIN_LABEL || line.address == PROGRAM_START);
+            if let Some(name) = symbol {
+                labels.insert(line.address, name.to_string());
+            } else if line.address == PROGRAM_START {
+                labels.insert(line.address, MAIN_LABEL.to_string());
+            } else if let Some(kind) = targets.get(&line.address) {
+                labels.insert(line.address, Listing::label_name(line.address, *kind));
+            }
+        }
+
+        Listing {
+            lines,
+            labels,
+            symbols: symbols.clone(),
+        }
+    }
+
+    pub fn line_index(&self, address: usize) -> Option<usize> {
+        let index = match self
+            .lines
+            .binary_search_by_key(&address, |line| line.address)
+        {
+            Ok(index) => index,
+            Err(0) => return None,
+            Err(index) => index - 1,
+        };
+        let line = &self.lines[index];
+
+        if address < line.address + line.bytes.len() {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_code(&self, address: usize) -> bool {
+        match self.line_index(address).map(|index| &self.lines[index]) {
+            Some(line) => line.address == address && line.kind != LineKind::Data,
+            None => false,
+        }
+    }
+
+    pub fn label(&self, address: usize) -> Option<&str> {
+        self.labels.get(&address).map(|label| label.as_str())
+    }
+
+    pub fn text(&self, line: &ListingLine) -> String {
+        match line.kind {
+            LineKind::Code(opcode) => {
+                Disassembler::disassemble_with_symbols(&opcode, &self.symbols)
+            }
+            LineKind::LongLoad(address) => match self.symbols.name(address as usize) {
+                Some(name) => format!("LD     I, {}", name),
+                None => format!("LD     I, {:04X}", address),
+            },
+            LineKind::Data => format!("DB    {}", Listing::hex_bytes(&line.bytes)),
+        }
+    }
+
+    pub fn octo_source(&self) -> Vec<String> {
+        let mut source: Vec<String> = Vec::new();
+
+        for line in self.lines.iter() {
+            if let Some(label) = self.label(line.address) {
+                source.push(format!(": {}", label));
+            }
+            let statement = match line.kind {
+                LineKind::Code(opcode) => self.octo_statement(opcode, &line.bytes),
+                LineKind::LongLoad(address) => match self.label(address as usize) {
+                    Some(label) => format!("i := long {}", label),
+                    None => format!("i := long 0x{:04X}", address),
+                },
+                LineKind::Data => Listing::octo_bytes(&line.bytes),
+            };
+            source.push(format!("  {:<24} # {:04X}", statement, line.address));
+        }
+
+        source
+    }
+
+    pub fn plain_source(&self) -> Vec<String> {
+        let mut source: Vec<String> = Vec::new();
+
+        for line in self.lines.iter() {
+            if let Some(label) = self.label(line.address) {
+                source.push(format!("{}:", label));
+            }
+            source.push(format!(
+                "{:04X}  {:<12} {}",
+                line.address,
+                Listing::hex_bytes(&line.bytes),
+                self.text(line)
+            ));
+        }
+
+        source
+    }
+
+    pub fn json_lines(&self) -> Vec<String> {
+        self.lines
+            .iter()
+            .map(|line| {
+                let kind = match line.kind {
+                    LineKind::Code(_) => "code",
+                    LineKind::LongLoad(_) => "long_load",
+                    LineKind::Data => "data",
+                };
+                let label = match self.label(line.address) {
+                    Some(label) => Listing::json_string(label),
+                    None => "null".to_string(),
+                };
+                format!(
+                    "{{\"address\": {}, \"bytes\": \"{}\", \"kind\": \"{}\", \"label\": {}, \"text\": {}}}",
+                    line.address,
+                    Listing::hex_bytes(&line.bytes).replace(' ', ""),
+                    kind,
+                    label,
+                    Listing::json_string(&self.text(line))
+                )
+            })
+            .collect()
+    }
+
+    fn octo_statement(&self, opcode: u16, bytes: &[u8]) -> String {
+        let label = self.label((opcode & 0x0FFF) as usize);
+        match (opcode >> 12, label) {
+            (0x1, Some(label)) => format!("jump {}", label),
+            (0x2, Some(label)) => label.to_string(),
+            (0xA, Some(label)) => format!("i := {}", label),
+            (0xB, Some(label)) => format!("jump0 {}", label),
+            _ => {
+                Disassembler::octo_statement(&opcode).unwrap_or_else(|| Listing::octo_bytes(bytes))
+            }
+        }
+    }
+
+    fn hex_bytes(bytes: &[u8]) -> String {
+        let bytes: Vec<String> = bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+        bytes.join(" ")
+    }
+
+    pub fn json_string(text: &str) -> String {
+        let mut escaped = String::from("\"");
+        for character in text.chars() {
+            match character {
+                '"' => escaped.push_str("\\\""),
+                '\\' => escaped.push_str("\\\\"),
+                c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+                c => escaped.push(c),
+            }
+        }
+        escaped.push('"');
+
+        escaped
+    }
+
+    fn octo_bytes(bytes: &[u8]) -> String {
+        let bytes: Vec<String> = bytes.iter().map(|byte| format!("0x{:02X}", byte)).collect();
+        bytes.join(" ")
+    }
+
+    fn read_opcode(code: &[u8], address: usize) -> Option<u16> {
+        let index = address.checked_sub(PROGRAM_START)?;
+        let high = *code.get(index)?;
+        let low = *code.get(index + 1)?;
+
+        Some((high as u16) << 8 | low as u16)
+    }
+
+    fn add_target(targets: &mut BTreeMap<usize, LabelKind>, address: usize, kind: LabelKind) {
+        let entry = targets.entry(address).or_insert(kind);
+        if kind > *entry {
+            *entry = kind;
+        }
+    }
+
+    fn label_name(address: usize, kind: LabelKind) -> String {
+        match kind {
+            LabelKind::Subroutine => format!("sub_{:04X}", address),
+            LabelKind::Jump => format!("label_{:04X}", address),
+            LabelKind::Data => format!("data_{:04X}", address),
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod listing;
+mod opcodes;
+
+pub use self::listing::{LineKind, Listing, ListingLine};
+pub use self::opcodes::{Disassembler, LONG_LOAD_OPCODE};
@@ -0,0 +1,231 @@
+use crate::controller::Listing;
+use crate::defines::InstructionSet;
+use crate::model::SymbolTable;
+
+pub const LONG_LOAD_OPCODE: u16 = 0xF000;
+const OCTO_DATA_BYTES: usize = 8;
+
+pub struct Disassembler {}
+
+impl Disassembler {
+    pub fn disassemble_list(code: &[u16]) -> Vec<String> {
+        let mut disassembled_code: Vec<String> = vec![String::with_capacity(10); code.len()];
+
+        for (i, opcode) in code.iter().enumerate() {
+            if i > 0 && code[i - 1] == LONG_LOAD_OPCODE {
+                disassembled_code[i] = format!("          {:04X}", opcode);
+            } else {
+                disassembled_code[i] = Disassembler::disassemble(opcode);
+            }
+        }
+
+        disassembled_code
+    }
+
+    pub fn octo_source(
+        code: &[u8],
+        symbols: &SymbolTable,
+        instruction_set: InstructionSet,
+    ) -> Vec<String> {
+        Listing::analyze(code, &[], OCTO_DATA_BYTES, symbols, instruction_set).octo_source()
+    }
+
+    pub fn octo_statement(opcode: &u16) -> Option<String> {
+        let nibbles = (
+            (opcode & 0xF000) >> 12,
+            (opcode & 0x0F00) >> 8,
+            (opcode & 0x00F0) >> 4,
+            (opcode & 0x000F),
+        );
+        let (x, y, n) = (nibbles.1, nibbles.2, nibbles.3);
+        let nn = opcode & 0x00FF;
+        let nnn = opcode & 0x0FFF;
+
+        let statement = match nibbles {
+            (0x0, 0x0, 0xC, _) => format!("scroll-down {}", n),
+            (0x0, 0x0, 0xD, _) => format!("scroll-up {}", n),
+            (0x0, 0x0, 0xE, 0x0) => "clear".to_string(),
+            (0x0, 0x0, 0xE, 0xE) => "return".to_string(),
+            (0x0, 0x0, 0xF, 0xB) => "scroll-right".to_string(),
+            (0x0, 0x0, 0xF, 0xC) => "scroll-left".to_string(),
+            (0x0, 0x0, 0xF, 0xD) => "exit".to_string(),
+            (0x0, 0x0, 0xF, 0xE) => "lores".to_string(),
+            (0x0, 0x0, 0xF, 0xF) => "hires".to_string(),
+            (0x1, _, _, _) => format!("jump 0x{:03X}", nnn),
+            (0x2, _, _, _) => format!(":call 0x{:03X}", nnn),
+            (0x3, _, _, _) => format!("if v{:X} != 0x{:02X} then", x, nn),
+            (0x4, _, _, _) => format!("if v{:X} == 0x{:02X} then", x, nn),
+            (0x5, _, _, 0x0) => format!("if v{:X} != v{:X} then", x, y),
+            (0x5, _, _, 0x2) => format!("save v{:X} - v{:X}", x, y),
+            (0x5, _, _, 0x3) => format!("load v{:X} - v{:X}", x, y),
+            (0x6, _, _, _) => format!("v{:X} := 0x{:02X}", x, nn),
+            (0x7, _, _, _) => format!("v{:X} += 0x{:02X}", x, nn),
+            (0x8, _, _, 0x0) => format!("v{:X} := v{:X}", x, y),
+            (0x8, _, _, 0x1) => format!("v{:X} |= v{:X}", x, y),
+            (0x8, _, _, 0x2) => format!("v{:X} &= v{:X}", x, y),
+            (0x8, _, _, 0x3) => format!("v{:X} ^= v{:X}", x, y),
+            (0x8, _, _, 0x4) => format!("v{:X} += v{:X}", x, y),
+            (0x8, _, _, 0x5) => format!("v{:X} -= v{:X}", x, y),
+            (0x8, _, _, 0x6) => format!("v{:X} >>= v{:X}", x, y),
+            (0x8, _, _, 0x7) => format!("v{:X} =- v{:X}", x, y),
+            (0x8, _, _, 0xE) => format!("v{:X} <<= v{:X}", x, y),
+            (0x9, _, _, 0x0) => format!("if v{:X} == v{:X} then", x, y),
+            (0xA, _, _, _) => format!("i := 0x{:03X}", nnn),
+            (0xB, _, _, _) => format!("jump0 0x{:03X}", nnn),
+            (0xC, _, _, _) => format!("v{:X} := random 0x{:02X}", x, nn),
+            (0xD, _, _, _) => format!("sprite v{:X} v{:X} {}", x, y, n),
+            (0xE, _, 0x9, 0xE) => format!("if v{:X} -key then", x),
+            (0xE, _, 0xA, 0x1) => format!("if v{:X} key then", x),
+            (0xF, _, 0x0, 0x1) => format!("plane {}", x),
+            (0xF, 0x0, 0x0, 0x2) => "audio".to_string(),
+            (0xF, _, 0x0, 0x7) => format!("v{:X} := delay", x),
+            (0xF, _, 0x0, 0xA) => format!("v{:X} := key", x),
+            (0xF, _, 0x1, 0x5) => format!("delay := v{:X}", x),
+            (0xF, _, 0x1, 0x8) => format!("buzzer := v{:X}", x),
+            (0xF, _, 0x1, 0xE) => format!("i += v{:X}", x),
+            (0xF, _, 0x2, 0x9) => format!("i := hex v{:X}", x),
+            (0xF, _, 0x3, 0x0) => format!("i := bighex v{:X}", x),
+            (0xF, _, 0x3, 0x3) => format!("bcd v{:X}", x),
+            (0xF, _, 0x3, 0xA) => format!("pitch := v{:X}", x),
+            (0xF, _, 0x5, 0x5) => format!("save v{:X}", x),
+            (0xF, _, 0x6, 0x5) => format!("load v{:X}", x),
+            (0xF, _, 0x7, 0x5) => format!("saveflags v{:X}", x),
+            (0xF, _, 0x8, 0x5) => format!("loadflags v{:X}", x),
+            _ => return None,
+        };
+
+        Some(statement)
+    }
+
+    pub fn disassemble_with_symbols(opcode: &u16, symbols: &SymbolTable) -> String {
+        let name = match symbols.name((opcode & 0x0FFF) as usize) {
+            Some(name) => name,
+            None => return Disassembler::disassemble(opcode),
+        };
+
+        match opcode >> 12 {
+            0x1 => format!("JP   {}", name),
+            0x2 => format!("CALL      {}", name),
+            0xA => format!("LD     I, {}", name),
+            0xB => format!("JP    V0, {}", name),
+            _ => Disassembler::disassemble(opcode),
+        }
+    }
+
+    pub fn disassemble(opcode: &u16) -> String {
+        let disassembled_code: String;
+
+        let nibbles = (
+            (opcode & 0xF000) >> 12,
+            (opcode & 0x0F00) >> 8,
+            (opcode & 0x00F0) >> 4,
+            (opcode & 0x000F),
+        );
+
+        let nn = nibbles.2 << 4 | nibbles.3;
+        let nnn = opcode & 0x0FFF;
+        match nibbles {
+            (0x0, 0x0, 0xb, _) => disassembled_code = format!("SCU   {:X}", nibbles.3),
+            (0x0, 0x0, 0xc, _) => disassembled_code = format!("SCD   {:X}", nibbles.3),
+            (0x0, 0x0, 0xd, _) => disassembled_code = format!("SCUP  {:X}", nibbles.3),
+            (0x0, 0x0, 0xe, 0x0) => disassembled_code = "CLS".to_string(),
+            (0x0, 0x0, 0xe, 0xe) => disassembled_code = "RET".to_string(),
+            (0x0, 0x0, 0xf, 0xb) => disassembled_code = "SCR".to_string(),
+            (0x0, 0x0, 0xf, 0xc) => disassembled_code = "SCL".to_string(),
+            (0x0, 0x0, 0xf, 0xd) => disassembled_code = "EXIT".to_string(),
+            (0x0, 0x0, 0xf, 0xe) => disassembled_code = "LOW".to_string(),
+            (0x0, 0x0, 0xf, 0xf) => disassembled_code = "HIGH".to_string(),
+            (0x1, _, _, _) => disassembled_code = format!("JP   {:03X}", nnn),
+            (0x2, _, _, _) => disassembled_code = format!("CALL      {:04X}", nnn),
+            (0x3, _, _, _) => disassembled_code = format!("SE    V{:X},  {:02X}", nibbles.1, nn),
+            (0x4, _, _, _) => disassembled_code = format!("SNE   V{:X},  {:02X}", nibbles.1, nn),
+            (0x5, _, _, 0x0) => {
+                disassembled_code = format!("SE    V{:X},  V{:X}", nibbles.1, nibbles.2)
+            }
+            (0x5, _, _, 0x2) => {
+                disassembled_code = format!("SAVE  V{:X} - V{:X}", nibbles.1, nibbles.2)
+            }
+            (0x5, _, _, 0x3) => {
+                disassembled_code = format!("LOAD  V{:X} - V{:X}", nibbles.1, nibbles.2)
+            }
+            (0x6, _, _, _) => disassembled_code = format!("LD    V{:X},  {:02X}", nibbles.1, nn),
+            (0x7, _, _, _) => disassembled_code = format!("ADD   V{:X},  {:02X}", nibbles.1, nn),
+            (0x8, _, _, 0x0) => {
+                disassembled_code = format!("LD    V{:X},  V{:X}", nibbles.1, nibbles.2)
+            }
+            (0x8, _, _, 0x1) => {
+                disassembled_code = format!("OR    V{:X},  V{:X}", nibbles.1, nibbles.2)
+            }
+            (0x8, _, _, 0x2) => {
+                disassembled_code = format!("AND   V{:X},  V{:X}", nibbles.1, nibbles.2)
+            }
+            (0x8, _, _, 0x3) => {
+                disassembled_code = format!("XOR   V{:X},  V{:X}", nibbles.1, nibbles.2)
+            }
+            (0x8, _, _, 0x4) => {
+                disassembled_code = format!("ADD   V{:X},  V{:X}", nibbles.1, nibbles.2)
+            }
+            (0x8, _, _, 0x5) => {
+                disassembled_code = format!("SUB   V{:X},  V{:X}", nibbles.1, nibbles.2)
+            }
+            (0x8, _, _, 0x6) => disassembled_code = format!("SHR   V{:X}", nibbles.1),
+            (0x8, _, _, 0x7) => {
+                disassembled_code = format!("SUBN  V{:X},  V{:X}", nibbles.1, nibbles.2)
+            }
+            (0x8, _, _, 0xE) => disassembled_code = format!("SHL   V{:X}", nibbles.1),
+            (0x9, _, _, 0x0) => {
+                disassembled_code = format!("SNE   V{:X},  V{:X}", nibbles.1, nibbles.2)
+            }
+            (0xA, _, _, _) => disassembled_code = format!("LD     I, {:04X}", nnn),
+            (0xB, _, _, _) => disassembled_code = format!("JP    V0, {:04X}", nnn),
+            (0xC, _, _, _) => disassembled_code = format!("RND   V{:X},  {:02X}", nibbles.1, nn),
+            (0xD, _, _, 0x0) => {
+                disassembled_code = format!("DRW   V{:X}   V{:X}", nibbles.1, nibbles.2)
+            }
+            (0xD, _, _, _) => {
+                disassembled_code =
+                    format!("DRW   V{:X}   V{:X}  {:X}", nibbles.1, nibbles.2, nibbles.3)
+            }
+            (0xE, _, 0x9, 0xE) => disassembled_code = format!("SKP   V{:X}", nibbles.1),
+            (0xE, _, 0xA, 0x1) => disassembled_code = format!("SKNP  V{:X}", nibbles.1),
+            (0xF, 0x0, 0x0, 0x0) => disassembled_code = "LD     I, LONG".to_string(),
+            (0xF, _, 0x0, 0x1) => disassembled_code = format!("PLANE {:X}", nibbles.1),
+            (0xF, 0x0, 0x0, 0x2) => disassembled_code = "AUDIO".to_string(),
+            (0xF, _, 0x0, 0x7) => disassembled_code = format!("LD    V{:X},   DT", nibbles.1),
+            (0xF, _, 0x0, 0xA) => disassembled_code = format!("LD    V{:X},   K", nibbles.1),
+            (0xF, _, 0x1, 0x5) => disassembled_code = format!("LD    DT, V{:X}", nibbles.1),
+            (0xF, _, 0x1, 0x8) => disassembled_code = format!("LD    ST, V{:X}", nibbles.1),
+            (0xF, _, 0x1, 0xE) => disassembled_code = format!("ADD    I, V{:X}", nibbles.1),
+            (0xF, _, 0x2, 0x9) => disassembled_code = format!("LD     F, V{:X}", nibbles.1),
+            (0xF, _, 0x3, 0x0) => disassembled_code = format!("LD    SF, V{:X}", nibbles.1),
+            (0xF, _, 0x3, 0x3) => disassembled_code = format!("BCD  [I], V{:X}", nibbles.1),
+            (0xF, _, 0x3, 0xA) => disassembled_code = format!("PITCH V{:X}", nibbles.1),
+            (0xF, _, 0x5, 0x5) => disassembled_code = format!("LD   [I], V{:X}", nibbles.1),
+            (0xF, _, 0x6, 0x5) => disassembled_code = format!("LD    V{:X}, [I]", nibbles.1),
+            (0xF, _, 0x7, 0x5) => disassembled_code = format!("LD    V{:X}, R", nibbles.1),
+            (0xF, _, 0x8, 0x5) => disassembled_code = format!("LD     R, V{:X}", nibbles.1),
+            _ => disassembled_code = "Unknown".to_string(),
+        }
+
+        disassembled_code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tells_the_scroll_up_variants_apart() {
+        assert_eq!(Disassembler::disassemble(&0x00B4), "SCU   4");
+        assert_eq!(Disassembler::disassemble(&0x00D4), "SCUP  4");
+    }
+
+    #[test]
+    fn writes_xo_chip_scroll_up_for_octo() {
+        assert_eq!(
+            Disassembler::octo_statement(&0x00D4),
+            Some("scroll-up 4".to_string())
+        );
+    }
+}
@@ -1,15 +1,15 @@
 use crate::controller::{
-    DebugManager, ProgramManager, StateManager, TimeManager, TimeTo, BASE_PROGRAM_SPEED,
+    DebugManager, FrameClock, ProgramManager, RateMeter, StateManager, TimeTo, BASE_PROGRAM_SPEED,
 };
-use crate::defines::{CpuState, DebugState, GameState, ProgramState};
-use crate::model::Cpu;
+use crate::defines::{CpuState, DebugState, GameState, ProgramState, TimingMode};
+use crate::model::{Chip8, RewindBuffer};
 use crate::view::View;
 
 use std::{
     any::TypeId,
     result::Result,
     sync::{
-        mpsc::{channel, Receiver, Sender, TryIter},
+        mpsc::{Sender, TryIter},
         Arc, Mutex,
     },
     thread,
@@ -17,43 +17,41 @@ use std::{
 };
 
 pub struct Emulator {
-    cpu: Cpu,
+    chip8: Chip8,
     _view: View,
     program_manager: Arc<Mutex<ProgramManager>>,
     debug_manager: Arc<Mutex<DebugManager>>,
     state_manager: Arc<Mutex<StateManager>>,
-    update_receiver: Receiver<TimeTo>,
+    frame_clock: FrameClock,
+    rate_meter: RateMeter,
     speed: u64,
-    instructioncounter: u64,
+    timing_mode: TimingMode,
     audio_sender: Sender<TimeTo>,
+    rewind_buffer: RewindBuffer,
 }
 
 impl Emulator {
     pub fn new(
-        new_cpu: Cpu,
+        new_chip8: Chip8,
         new_program_manager: Arc<Mutex<ProgramManager>>,
         new_debug_manager: Arc<Mutex<DebugManager>>,
         new_state_manager: Arc<Mutex<StateManager>>,
         new_view: View,
         new_audio_sender: Sender<TimeTo>,
+        new_rewind_buffer: RewindBuffer,
     ) -> Emulator {
-        let (new_sender, new_receiver) = channel();
-
-        std::thread::spawn(move || {
-            let mut time_manager = TimeManager::new(new_sender);
-            time_manager.start_clock();
-        });
-
         Emulator {
-            cpu: new_cpu,
+            chip8: new_chip8,
             _view: new_view,
             program_manager: new_program_manager,
             debug_manager: new_debug_manager,
             state_manager: new_state_manager,
-            update_receiver: new_receiver,
+            frame_clock: FrameClock::new(),
+            rate_meter: RateMeter::new(),
             speed: BASE_PROGRAM_SPEED,
-            instructioncounter: 0,
+            timing_mode: TimingMode::Flat,
             audio_sender: new_audio_sender,
+            rewind_buffer: new_rewind_buffer,
         }
     }
 
@@ -71,13 +69,20 @@ impl Emulator {
                 ProgramState::NewProgram => self.new_program(),
                 ProgramState::Running => self.running(),
                 ProgramState::Debug(DebugState::Step) => self.step(),
+                ProgramState::Debug(DebugState::StepBack) => self.step_back(),
                 ProgramState::Restart => self.new_program(),
                 ProgramState::Stopped => self.check_debug(),
                 ProgramState::Idle => self.idle(),
+                ProgramState::SaveState(slot) => self.save_state(slot),
+                ProgramState::LoadState(slot) => self.load_state(slot),
+                ProgramState::Rewind => self.rewind(),
                 ProgramState::Quit => break 'running,
                 _ => {}
             }
-            self.debug_manager.lock().unwrap().check_breakpoint();
+            if current_state != ProgramState::Running && current_state != ProgramState::Rewind {
+                self.frame_clock.reset();
+            }
+            self.update_rates();
             self.update_state(current_state);
             thread::sleep(Duration::from_micros(1000));
         }
@@ -91,53 +96,102 @@ impl Emulator {
         thread::sleep(Duration::from_millis(10));
     }
 
-    fn run_code(&mut self) {
-        self.cpu.run_opcode();
-        self.instructioncounter += 1;
+    // Returns false when the program stopped on a fault, a breakpoint or a watchpoint
+    fn run_code(&mut self) -> bool {
+        self.debug_manager.lock().unwrap().update_access_logging();
+        let result = self.chip8.step();
+        let mut debug_manager = self.debug_manager.lock().unwrap();
+        if let Err(fault) = result {
+            debug_manager.stop_on_fault(&fault);
+            return false;
+        }
+        debug_manager.trace_step();
+        debug_manager.profile_step();
+        debug_manager.check_watchpoints();
+        debug_manager.check_breakpoint();
+        drop(debug_manager);
+
+        self.state_manager.lock().unwrap().get_game_state() == GameState::Running
     }
 
-    fn refresh(&mut self) {
-        self.speed = self.program_manager.lock().unwrap().get_speed();
-        self.run_code_based_on_timer();
+    // Runs the instructions of one frame, `speed` of them or as many as fit in
+    // the VIP cycle budget, and ticks the timers once. An interrupted frame
+    // leaves the timers untouched
+    fn run_frame(&mut self) -> bool {
+        self.update_history();
+        let mut manager = self.program_manager.lock().unwrap();
+        self.speed = manager.get_speed();
+        self.timing_mode = manager.get_timing_mode();
+        drop(manager);
+        self.chip8.set_instructions_per_frame(self.speed);
+        self.chip8.set_timing_mode(self.timing_mode);
+
+        self.chip8.begin_frame();
+        while !self.chip8.is_frame_finished() {
+            if !self.run_code() {
+                self.rate_meter
+                    .count_instructions(self.chip8.get_frame_instructions());
+                return false;
+            }
+        }
+
         self.refresh_cpu_timer();
-        self.instructioncounter = 0;
+        self.rate_meter
+            .count_instructions(self.chip8.get_frame_instructions());
+        self.debug_manager.lock().unwrap().profile_frame();
+        self.rewind_buffer.push(&self.chip8.save_state());
+
+        true
     }
 
-    fn run_code_based_on_timer(&mut self) {
-        while self.instructioncounter < self.speed {
-            self.run_code();
+    fn update_rates(&mut self) {
+        if let Some((timer_rate, instruction_rate)) = self.rate_meter.take_rates() {
+            self.program_manager
+                .lock()
+                .unwrap()
+                .set_rates(timer_rate, instruction_rate);
         }
     }
 
     fn refresh_cpu_timer(&mut self) {
-        self.cpu.tick_timer();
+        self.chip8.tick_timers();
+        self.rate_meter.count_timer_tick();
         self.sound_check();
     }
 
     fn step(&mut self) {
+        self.update_history();
         self.run_code();
         self.refresh_cpu_timer();
     }
 
-    fn running(&mut self) {
-        self.run_remaining_opcodes();
-        self.check_time();
+    fn step_back(&mut self) {
+        if !self.chip8.step_back() {
+            println!("No earlier instruction recorded");
+        }
+        self.sound_check();
     }
 
-    fn run_remaining_opcodes(&mut self) {
-        if self.instructioncounter <= self.speed {
-            self.run_code();
-        }
+    fn update_history(&mut self) {
+        let debug_state = self.state_manager.lock().unwrap().get_debug_state();
+        self.chip8
+            .set_history_enabled(debug_state == DebugState::Enabled);
     }
 
-    fn check_time(&mut self) {
-        let mut is_ok = false;
-        for _ in self.update_receiver.try_iter() {
-            is_ok = true;
+    fn running(&mut self) {
+        for _ in 0..self.frame_clock.elapsed_frames() {
+            if !self.run_frame() {
+                break;
+            }
         }
+    }
 
-        if is_ok {
-            self.refresh();
+    fn rewind(&mut self) {
+        for _ in 0..self.frame_clock.elapsed_frames() {
+            if let Some(state) = self.rewind_buffer.pop() {
+                self.chip8.load_state(&state);
+                self.sound_check();
+            }
         }
     }
 
@@ -150,18 +204,53 @@ impl Emulator {
 
     fn initialize(&mut self) {
         let mut manager = self.program_manager.lock().unwrap();
-        manager.initialize();
-        self.cpu.load_program_code(&manager.get_file_content());
+        if let Err(error) = self.chip8.load_program(&manager.get_file_content()) {
+            println!("{}", error);
+        }
     }
 
     fn new_program(&mut self) {
         let mut manager = self.program_manager.lock().unwrap();
-        self.cpu.reset();
-        self.cpu.load_program_code(&manager.get_file_content());
+        if let Err(error) = self.chip8.load_program(&manager.get_file_content()) {
+            println!("{}", error);
+        }
+        self.rewind_buffer.clear();
+        let mut debug_manager = self.debug_manager.lock().unwrap();
+        debug_manager.reset_trace();
+        debug_manager.reset_profile();
+        if let Some(symbols) = manager.take_symbols() {
+            debug_manager.set_symbols(symbols);
+        }
+    }
+
+    fn save_state(&mut self, slot: u8) {
+        let state = self.chip8.save_state();
+        self.program_manager
+            .lock()
+            .unwrap()
+            .write_save_state(slot, &state);
+    }
+
+    fn load_state(&mut self, slot: u8) {
+        // The program manager is not held while the machine locks the keypad,
+        // the input thread takes them in the opposite order
+        let result = self.program_manager.lock().unwrap().read_save_state(slot);
+        match result {
+            Ok(state) => {
+                self.chip8.load_state(&state);
+                self.speed = state.speed;
+                self.timing_mode = state.timing_mode;
+                self.program_manager
+                    .lock()
+                    .unwrap()
+                    .apply_save_state(&state);
+            }
+            Err(error) => println!("{}", error),
+        }
     }
 
     fn sound_check(&mut self) {
-        if self.cpu.play_sound() {
+        if self.chip8.is_sound_playing() {
             self.audio_sender.send(TimeTo::PlaySound).unwrap();
         } else {
             self.audio_sender.send(TimeTo::StopSound).unwrap();
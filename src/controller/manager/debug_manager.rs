@@ -1,19 +1,32 @@
-use crate::controller::StateManager;
+use crate::controller::{
+    Disassembler, FileManager, StateManager, TraceLogger, TraceStep, PROFILE_REPORT_PATH,
+};
 use crate::defines::{
     font_constants::FONTSIZE_LINE,
-    layout_constants::{OPCODE_HEIGHT, OPCODE_START_X, OPCODE_START_Y, OPCODE_WIDTH},
-    memory_constants::{PROGRAM_START, VARIABLES_COUNT},
-    DebugState, GameState, ProgramState,
+    layout_constants::{
+        HIGHLIGHT_PADDING, LINE_PADDING, MEMORY_HEIGHT, MEMORY_REGISTER_ROWS, MEMORY_START_X,
+        MEMORY_START_Y, MEMORY_WIDTH, OPCODE_HEIGHT, OPCODE_START_X, OPCODE_START_Y, OPCODE_WIDTH,
+        STACK_FRAME_LINES, STACK_HEIGHT, STACK_START_X, STACK_START_Y, STACK_WIDTH,
+    },
+    memory_constants::{PROGRAM_STEP, STACKSIZE, VARIABLES_COUNT},
+    DebugState, GameState, ProgramState, Register, WatchKind,
+};
+use crate::model::{
+    Breakpoint, BreakpointSpec, CpuFault, DebugProperties, MemoryAccess, RegisterEdit, RunTarget,
+    SymbolTable, WatchHit, Watchpoint,
 };
-use crate::model::{DebugProperties, MemoryAccess};
 use sdl2::keyboard::Keycode;
-use std::sync::{Arc, Mutex};
+use std::{
+    result,
+    sync::{Arc, Mutex},
+};
 
 pub struct DebugManager {
     current_state: DebugState,
     debug_properties: Arc<Mutex<DebugProperties>>,
     memory_access: Arc<Mutex<MemoryAccess>>,
     state_manager: Arc<Mutex<StateManager>>,
+    trace_logger: Arc<Mutex<TraceLogger>>,
 }
 
 impl DebugManager {
@@ -21,12 +34,14 @@ impl DebugManager {
         new_memory_access: Arc<Mutex<MemoryAccess>>,
         new_state_manager: Arc<Mutex<StateManager>>,
         new_debug_properties: Arc<Mutex<DebugProperties>>,
+        new_trace_logger: Arc<Mutex<TraceLogger>>,
     ) -> DebugManager {
         DebugManager {
             current_state: DebugState::Disabled,
             debug_properties: new_debug_properties,
             memory_access: new_memory_access,
             state_manager: new_state_manager,
+            trace_logger: new_trace_logger,
         }
     }
 
@@ -35,7 +50,19 @@ impl DebugManager {
             Keycode::F3 => self.toggle_enabled(),
             Keycode::F6 => self.step_trough(),
             Keycode::F7 => self.set_breakpoint_on_current_line(),
-            Keycode::F8 => {}
+            Keycode::F8 => self.step_back(),
+            Keycode::F9 => self.step_over(),
+            Keycode::F10 => self.step_out(),
+            Keycode::F11 => self.run_to_cursor(),
+            Keycode::F12 => self.trace_logger.lock().unwrap().toggle(),
+            Keycode::H => {
+                let profiling = !self.debug_properties.lock().unwrap().profiling;
+                self.set_profiling(profiling);
+            }
+            Keycode::J => match self.export_profile(PROFILE_REPORT_PATH) {
+                Ok(()) => println!("Wrote profile to {}", PROFILE_REPORT_PATH),
+                Err(error) => println!("{}", error),
+            },
             _ => {}
         }
     }
@@ -44,68 +71,553 @@ impl DebugManager {
         self.state_manager.lock().unwrap().toggle_debug();
     }
 
-    fn step_trough(&mut self) {
+    pub fn pause(&mut self) {
+        let mut state_manager = self.state_manager.lock().unwrap();
+        if state_manager.get_debug_state() == DebugState::Disabled {
+            state_manager.toggle_debug();
+        }
+        if state_manager.get_game_state() == GameState::Running {
+            state_manager.toggle_continue();
+        }
+    }
+
+    pub fn resume(&mut self) {
+        let mut state_manager = self.state_manager.lock().unwrap();
+        if state_manager.get_game_state() == GameState::Stopped {
+            state_manager.toggle_continue();
+        }
+    }
+
+    pub fn stop_on_fault(&mut self, fault: &CpuFault) {
+        println!("Error: {}", fault);
+        self.debug_properties.lock().unwrap().run_target = None;
+        self.pause();
+    }
+
+    pub fn step_trough(&mut self) {
         self.state_manager
             .lock()
             .unwrap()
             .update_state(ProgramState::Debug(DebugState::Step));
     }
 
+    fn step_back(&mut self) {
+        self.state_manager
+            .lock()
+            .unwrap()
+            .update_state(ProgramState::Debug(DebugState::StepBack));
+    }
+
+    pub fn step_over(&mut self) {
+        if !self.is_paused() {
+            return;
+        }
+
+        let mut memory_access = self.memory_access.lock().unwrap();
+        let program_counter = memory_access.get_program_counter();
+        let stack_pointer = memory_access.get_stack_pointer();
+        let is_call = memory_access.get_memory_at(program_counter).unwrap_or(0) & 0xF0 == 0x20;
+        drop(memory_access);
+
+        if is_call {
+            self.run_to(RunTarget {
+                address: Some(program_counter + PROGRAM_STEP),
+                stack_pointer: Some(stack_pointer),
+            });
+        } else {
+            self.step_trough();
+        }
+    }
+
+    pub fn step_out(&mut self) {
+        if !self.is_paused() {
+            return;
+        }
+
+        let stack_pointer = self.memory_access.lock().unwrap().get_stack_pointer();
+        if stack_pointer == 0 {
+            println!("Error: Not inside a subroutine");
+            return;
+        }
+
+        self.run_to(RunTarget {
+            address: None,
+            stack_pointer: Some(stack_pointer - 1),
+        });
+    }
+
+    pub fn run_to_cursor(&mut self) {
+        if !self.is_paused() {
+            return;
+        }
+
+        let cursor = self.debug_properties.lock().unwrap().cursor;
+        match cursor {
+            Some(address) => self.run_to_address(address),
+            None => println!("Error: No cursor set, right click a line in the opcode panel"),
+        }
+    }
+
+    pub fn run_to_address(&mut self, address: usize) {
+        self.run_to(RunTarget {
+            address: Some(address),
+            stack_pointer: None,
+        });
+    }
+
+    fn run_to(&mut self, target: RunTarget) {
+        self.debug_properties.lock().unwrap().run_target = Some(target);
+        self.state_manager.lock().unwrap().toggle_continue();
+    }
+
+    fn is_paused(&self) -> bool {
+        let mut state_manager = self.state_manager.lock().unwrap();
+        state_manager.get_debug_state() == DebugState::Enabled
+            && state_manager.get_game_state() == GameState::Stopped
+    }
+
     fn set_breakpoint_on_current_line(&mut self) {
         let state = self.state_manager.lock().unwrap().get_debug_state();
         if state == DebugState::Enabled {
-            let line = self.memory_access.lock().unwrap().get_program_counter() - PROGRAM_START;
-            self.toggle_breakpoint(line);
+            let address = self.memory_access.lock().unwrap().get_program_counter();
+            self.toggle_breakpoint(address);
         }
     }
 
     pub fn set_breakpoint_on_mouse_click(&mut self, x: &i32, y: &i32) {
-        let mut state_manager = self.state_manager.lock().unwrap();
-        if state_manager.get_debug_state() == DebugState::Enabled
-            && state_manager.get_game_state() == GameState::Stopped
+        if self.is_paused() {
+            if let Some(address) = self.check_mouse_coordinates(x, y) {
+                self.toggle_breakpoint(address);
+            }
+        }
+    }
+
+    pub fn set_cursor_on_mouse_click(&mut self, x: &i32, y: &i32) {
+        if self.is_paused() {
+            if let Some(address) = self.check_mouse_coordinates(x, y) {
+                self.debug_properties.lock().unwrap().cursor = Some(address);
+            }
+        }
+    }
+
+    pub fn select_register_on_mouse_click(&mut self, x: &i32, y: &i32) {
+        let register = if self.is_paused() {
+            DebugManager::check_register_coordinates(x, y)
+        } else {
+            None
+        };
+
+        self.debug_properties.lock().unwrap().register_edit =
+            register.map(|register| RegisterEdit {
+                register,
+                input: String::new(),
+            });
+    }
+
+    pub fn select_stack_frame_on_mouse_click(&mut self, x: &i32, y: &i32) {
+        if !self.is_paused() {
+            return;
+        }
+        if *x <= STACK_START_X || *x >= STACK_START_X + STACK_WIDTH as i32 {
+            return;
+        }
+        if *y <= STACK_START_Y || *y >= STACK_START_Y + STACK_HEIGHT as i32 {
+            return;
+        }
+
+        let row = ((*y - STACK_START_Y - HIGHLIGHT_PADDING) / (FONTSIZE_LINE as i32 + LINE_PADDING))
+            as usize;
+        if row == 0 || row >= STACKSIZE - 1 {
+            return;
+        }
+
+        let frames = self.memory_access.lock().unwrap().get_call_frames();
+        if let Some((call_site, _)) = frames.get((row - 1) / STACK_FRAME_LINES) {
+            let mut properties = self.debug_properties.lock().unwrap();
+            properties.opcode_scroll = Some(*call_site);
+            properties.cursor = Some(*call_site);
+        }
+    }
+
+    pub fn is_editing_register(&self) -> bool {
+        self.debug_properties
+            .lock()
+            .unwrap()
+            .register_edit
+            .is_some()
+    }
+
+    pub fn edit_register(&mut self, key: Keycode) {
+        if !self.is_paused() {
+            self.debug_properties.lock().unwrap().register_edit = None;
+            return;
+        }
+
+        let mut properties = self.debug_properties.lock().unwrap();
+        let edit = match properties.register_edit.as_mut() {
+            Some(edit) => edit,
+            None => return,
+        };
+
+        match key {
+            Keycode::Return | Keycode::KpEnter => {
+                let edit = properties.register_edit.take().unwrap();
+                drop(properties);
+                self.apply_register_edit(&edit);
+            }
+            Keycode::Escape => properties.register_edit = None,
+            Keycode::Backspace => {
+                edit.input.pop();
+            }
+            _ => {
+                let name = key.name();
+                let digit = name.trim_start_matches("Keypad ");
+                if digit.len() == 1
+                    && digit.chars().all(|c| c.is_ascii_hexdigit())
+                    && edit.input.len() < edit.register.digits()
+                {
+                    edit.input.push_str(digit);
+                }
+            }
+        }
+    }
+
+    fn apply_register_edit(&mut self, edit: &RegisterEdit) {
+        if edit.input.is_empty() {
+            return;
+        }
+
+        let value = usize::from_str_radix(&edit.input, 16).unwrap_or(0);
+        if !self
+            .memory_access
+            .lock()
+            .unwrap()
+            .set_register(edit.register, value)
         {
-            if let Some(line) = self.check_mouse_coordinates(x, y) {
-                self.toggle_breakpoint(line as usize);
+            println!(
+                "Error: Invalid value for {}: {}",
+                edit.register.name(),
+                edit.input
+            );
+        }
+    }
+
+    fn check_register_coordinates(x: &i32, y: &i32) -> Option<Register> {
+        if *x <= MEMORY_START_X || *x >= MEMORY_START_X + MEMORY_WIDTH as i32 {
+            return None;
+        }
+        if *y <= MEMORY_START_Y || *y >= MEMORY_START_Y + MEMORY_HEIGHT as i32 {
+            return None;
+        }
+
+        let row = ((*y - MEMORY_START_Y - HIGHLIGHT_PADDING)
+            / (FONTSIZE_LINE as i32 + LINE_PADDING)) as usize;
+        if *x < MEMORY_START_X + MEMORY_WIDTH as i32 / 2 {
+            if row < VARIABLES_COUNT {
+                return Some(Register::Variable(VARIABLES_COUNT - 1 - row));
             }
+            return None;
         }
+
+        MEMORY_REGISTER_ROWS.get(row).cloned().unwrap_or(None)
     }
 
-    fn check_mouse_coordinates(&self, x: &i32, y: &i32) -> Option<i32> {
+    fn check_mouse_coordinates(&self, x: &i32, y: &i32) -> Option<usize> {
         if *x > OPCODE_START_X && *x < OPCODE_START_X + OPCODE_WIDTH as i32 {
             if *y > OPCODE_START_Y && *y < OPCODE_START_Y + OPCODE_HEIGHT as i32 {
-                return Some((*y - OPCODE_START_Y) / FONTSIZE_LINE as i32);
+                let row = ((*y - OPCODE_START_Y - HIGHLIGHT_PADDING)
+                    / (FONTSIZE_LINE as i32 + LINE_PADDING)) as usize;
+                return self
+                    .debug_properties
+                    .lock()
+                    .unwrap()
+                    .opcode_addresses
+                    .get(row)
+                    .cloned();
             }
         }
 
         None
     }
 
-    fn toggle_breakpoint(&self, line: usize) {
+    fn toggle_breakpoint(&self, address: usize) {
         let mut properties = self.debug_properties.lock().unwrap();
-        if properties.breakpoints.contains_key(&line) {
-            properties.breakpoints.remove(&line);
-        } else if properties.breakpoints.len() < VARIABLES_COUNT {
-            let opcode = self
-                .memory_access
-                .lock()
-                .unwrap()
-                .get_opcode_at(line)
-                .unwrap();
-            properties.breakpoints.insert(line, opcode);
+        if properties.breakpoints.remove(&address).is_none() {
+            properties.breakpoints.insert(address, Breakpoint::new());
         }
     }
 
+    pub fn set_breakpoint(&mut self, spec: &BreakpointSpec) -> result::Result<(), String> {
+        let mut properties = self.debug_properties.lock().unwrap();
+        let breakpoint = Breakpoint::with_condition(
+            spec.condition.as_deref(),
+            spec.ignore_count,
+            &properties.symbols,
+        )?;
+        properties.breakpoints.insert(spec.address, breakpoint);
+
+        Ok(())
+    }
+
+    pub fn remove_breakpoint(&mut self, address: usize) -> bool {
+        self.debug_properties
+            .lock()
+            .unwrap()
+            .breakpoints
+            .remove(&address)
+            .is_some()
+    }
+
     pub fn check_breakpoint(&mut self) {
+        let mut state_manager = self.state_manager.lock().unwrap();
         let mut memory_access = self.memory_access.lock().unwrap();
+        let mut properties = self.debug_properties.lock().unwrap();
+        if state_manager.get_game_state() != GameState::Running
+            || state_manager.get_debug_state() != DebugState::Enabled
+        {
+            properties.run_target = None;
+            return;
+        }
+
+        let program_counter = memory_access.get_program_counter();
+
+        if let Some(target) = properties.run_target {
+            if target.reached(program_counter, memory_access.get_stack_pointer()) {
+                properties.run_target = None;
+                state_manager.update_state(ProgramState::Debug(DebugState::Breakpoint));
+                return;
+            }
+        }
+
+        if let Some(breakpoint) = properties.breakpoints.get_mut(&program_counter) {
+            if breakpoint.hit(&mut memory_access) {
+                properties.run_target = None;
+                state_manager.update_state(ProgramState::Debug(DebugState::Breakpoint));
+            }
+        }
+    }
+
+    pub fn set_symbols(&mut self, symbols: SymbolTable) {
+        let symbols = Arc::new(symbols);
+        self.trace_logger
+            .lock()
+            .unwrap()
+            .set_symbols(Arc::clone(&symbols));
+        self.debug_properties.lock().unwrap().symbols = symbols;
+    }
+
+    pub fn load_symbols(&mut self, path: &str) -> result::Result<usize, String> {
+        let symbols = FileManager::read_symbols(path)?;
+        let count = symbols.len();
+        self.set_symbols(symbols);
+
+        Ok(count)
+    }
+
+    pub fn set_watchpoint(&mut self, watchpoint: Watchpoint) {
+        self.debug_properties
+            .lock()
+            .unwrap()
+            .watchpoints
+            .push(watchpoint);
+    }
+
+    pub fn remove_watchpoint(&mut self, index: usize) -> bool {
+        let mut properties = self.debug_properties.lock().unwrap();
+        if index < properties.watchpoints.len() {
+            properties.watchpoints.remove(index);
+            return true;
+        }
+
+        false
+    }
+
+    pub fn find_watchpoint(&self, start: usize, end: usize, kind: WatchKind) -> Option<usize> {
+        self.debug_properties
+            .lock()
+            .unwrap()
+            .watchpoints
+            .iter()
+            .position(|watchpoint| {
+                watchpoint.start == start && watchpoint.end == end && watchpoint.kind == kind
+            })
+    }
+
+    pub fn get_last_watch_hit(&self) -> Option<WatchHit> {
+        self.debug_properties.lock().unwrap().last_watch_hit
+    }
+
+    // Called before every instruction, so the accesses of the first instruction
+    // after a watchpoint was added or debug mode was enabled are seen as well
+    pub fn update_access_logging(&mut self) {
         let mut state_manager = self.state_manager.lock().unwrap();
-        let breakpoints = self.debug_properties.lock().unwrap().breakpoints.clone();
+        let mut memory_access = self.memory_access.lock().unwrap();
+        let properties = self.debug_properties.lock().unwrap();
+
+        memory_access.set_access_logging(
+            state_manager.get_debug_state() == DebugState::Enabled
+                && !properties.watchpoints.is_empty(),
+        );
+    }
 
-        if let Some(_breakpoint) =
-            breakpoints.get(&(memory_access.get_program_counter() - PROGRAM_START))
+    pub fn check_watchpoints(&mut self) {
+        let mut state_manager = self.state_manager.lock().unwrap();
+        let mut memory_access = self.memory_access.lock().unwrap();
+        let mut properties = self.debug_properties.lock().unwrap();
+
+        if state_manager.get_debug_state() != DebugState::Enabled
+            || properties.watchpoints.is_empty()
         {
+            return;
+        }
+
+        let program_counter = memory_access.get_instruction_address();
+        let mut triggered: Option<WatchHit> = None;
+        for event in memory_access.take_access_log().iter() {
+            for watchpoint in properties.watchpoints.iter_mut() {
+                if watchpoint.matches(event) {
+                    watchpoint.hit_count += 1;
+                    println!(
+                        "Watchpoint {:04X}-{:04X} {}: {:?} of {:04X} at PC {:04X}, {:02X} -> {:02X}",
+                        watchpoint.start,
+                        watchpoint.end,
+                        watchpoint.kind.name(),
+                        event.operation,
+                        event.address,
+                        program_counter,
+                        event.old_value,
+                        event.new_value
+                    );
+                    triggered = Some(WatchHit {
+                        program_counter,
+                        event: *event,
+                    });
+                }
+            }
+        }
+
+        if triggered.is_some() {
+            properties.last_watch_hit = triggered;
+            properties.run_target = None;
             state_manager.update_state(ProgramState::Debug(DebugState::Breakpoint));
         }
     }
 
+    pub fn set_trace(&mut self, enabled: bool) -> result::Result<(), String> {
+        self.trace_logger.lock().unwrap().set_enabled(enabled)
+    }
+
+    pub fn set_trace_range(&mut self, range: Option<(usize, usize)>) {
+        self.trace_logger.lock().unwrap().set_range(range);
+    }
+
+    pub fn reset_trace(&mut self) {
+        self.trace_logger.lock().unwrap().reset_cycles();
+    }
+
+    pub fn trace_step(&mut self) {
+        let mut trace_logger = self.trace_logger.lock().unwrap();
+        trace_logger.count_cycle();
+        if !trace_logger.is_enabled() {
+            return;
+        }
+
+        let mut memory_access = self.memory_access.lock().unwrap();
+        let address = memory_access.get_instruction_address();
+        if trace_logger.wants(address) {
+            trace_logger.log(&TraceStep {
+                address,
+                opcode: memory_access.get_opcode(),
+                variable_register: memory_access.get_variable_register(),
+                index_register: memory_access.get_index_register(),
+                stack_pointer: memory_access.get_stack_pointer(),
+            });
+        }
+    }
+
+    pub fn set_profiling(&mut self, enabled: bool) {
+        self.debug_properties.lock().unwrap().profiling = enabled;
+        println!("Profiling {}", if enabled { "started" } else { "stopped" });
+    }
+
+    pub fn reset_profile(&mut self) {
+        self.debug_properties.lock().unwrap().profile.clear();
+    }
+
+    pub fn profile_step(&mut self) {
+        if !self.debug_properties.lock().unwrap().profiling {
+            return;
+        }
+
+        let mut memory_access = self.memory_access.lock().unwrap();
+        let address = memory_access.get_instruction_address();
+        let opcode = memory_access.get_opcode();
+        self.debug_properties
+            .lock()
+            .unwrap()
+            .profile
+            .record(address, opcode);
+    }
+
+    pub fn profile_frame(&mut self) {
+        let mut properties = self.debug_properties.lock().unwrap();
+        if properties.profiling {
+            properties.profile.end_frame();
+        }
+    }
+
+    pub fn export_profile(&mut self, path: &str) -> result::Result<(), String> {
+        let mut memory_access = self.memory_access.lock().unwrap();
+        let properties = self.debug_properties.lock().unwrap();
+        let profile = &properties.profile;
+        let total = profile.get_total_executions().max(1) as f64;
+        let draw_calls = profile.get_draw_calls();
+
+        let mut report = format!(
+            "# {} instructions, {} draw calls in {} frames, last frame {}, max {}\n",
+            profile.get_total_executions(),
+            draw_calls.total,
+            draw_calls.frames,
+            draw_calls.last_frame,
+            draw_calls.max_frame
+        );
+        report.push_str("address,executions,percent,instruction\n");
+        for (address, count) in profile.hottest_addresses() {
+            let instruction = match memory_access.get_memory_range(address, 2) {
+                Some(bytes) => {
+                    Disassembler::disassemble(&((bytes[0] as u16) << 8 | bytes[1] as u16))
+                }
+                None => String::new(),
+            };
+            report.push_str(&format!(
+                "{:04X},{},{:.2},{}\n",
+                address,
+                count,
+                count as f64 * 100.0 / total,
+                DebugManager::csv_field(&instruction)
+            ));
+        }
+
+        report.push_str("\nsubroutine,calls,instructions,self_instructions,percent\n");
+        for (address, subroutine) in profile.hottest_subroutines() {
+            report.push_str(&format!(
+                "{:04X},{},{},{},{:.2}\n",
+                address,
+                subroutine.calls,
+                subroutine.instructions,
+                subroutine.self_instructions,
+                subroutine.instructions as f64 * 100.0 / total
+            ));
+        }
+
+        FileManager::write_report(path, &report)
+    }
+
+    // The disassembly is padded into columns and separates operands with commas
+    fn csv_field(text: &str) -> String {
+        let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+        format!("\"{}\"", text.replace('"', "\"\""))
+    }
+
     pub fn toggle_debug_mode(&mut self) {}
 }
@@ -1,7 +1,11 @@
+#[cfg(feature = "gui")]
 mod debug_manager;
+#[cfg(feature = "gui")]
 mod program_manager;
 mod state_manager;
 
+#[cfg(feature = "gui")]
 pub use self::debug_manager::DebugManager;
+#[cfg(feature = "gui")]
 pub use self::program_manager::{ProgramManager, BASE_PROGRAM_SPEED};
 pub use self::state_manager::StateManager;
@@ -1,9 +1,12 @@
 use crate::controller::{FileManager, StateManager};
-use crate::defines::ProgramState;
-use crate::model::{GameProperties, MemoryAccess};
+use crate::defines::{game_constants::PALETTE_SIZE, ProgramState, QuirkProfile, TimingMode};
+use crate::model::{GameProperties, MachineState, MemoryAccess, Quirks, SymbolTable};
 
 use sdl2::keyboard::Keycode;
-use std::sync::{Arc, Mutex};
+use std::{
+    result,
+    sync::{Arc, Mutex},
+};
 
 pub const BASE_PROGRAM_SPEED: u64 = 10;
 pub const MINIMUM_SPEED: u64 = 1;
@@ -15,6 +18,7 @@ pub struct ProgramManager {
     state_manager: Arc<Mutex<StateManager>>,
     memory_access: Arc<Mutex<MemoryAccess>>,
     game_properties: Arc<Mutex<GameProperties>>,
+    quirks: Arc<Mutex<Quirks>>,
 }
 
 impl ProgramManager {
@@ -23,12 +27,14 @@ impl ProgramManager {
         new_state_manager: Arc<Mutex<StateManager>>,
         new_memory_access: Arc<Mutex<MemoryAccess>>,
         new_game_properties: Arc<Mutex<GameProperties>>,
+        new_quirks: Arc<Mutex<Quirks>>,
     ) -> ProgramManager {
         ProgramManager {
             file_manager: new_file_manager,
             state_manager: new_state_manager,
             memory_access: new_memory_access,
             game_properties: new_game_properties,
+            quirks: new_quirks,
         }
     }
 
@@ -46,6 +52,9 @@ impl ProgramManager {
             Keycode::Plus => self.increase_speed(),
             Keycode::Minus => self.decrease_speed(),
             Keycode::L => self.open_file_dialog(),
+            Keycode::P => self.next_quirk_profile(),
+            Keycode::T => self.next_timing_mode(),
+            Keycode::Tab => self.next_control_page(),
             _ => {}
         }
     }
@@ -58,7 +67,10 @@ impl ProgramManager {
     }
 
     fn open_editor(&mut self) {
-        self.file_manager.open_editor();
+        match self.file_manager.open_editor() {
+            Ok(path) => self.new_file(&path),
+            Err(error) => println!("{}", error),
+        }
     }
 
     fn increase_speed(&mut self) {
@@ -75,6 +87,107 @@ impl ProgramManager {
         }
     }
 
+    pub fn set_speed(&mut self, speed: u64) -> result::Result<(), String> {
+        if !(MINIMUM_SPEED..=MAXIMUM_SPEED).contains(&speed) {
+            return Err(format!(
+                "Error: The speed must be between {} and {} instructions per frame, got: {}",
+                MINIMUM_SPEED, MAXIMUM_SPEED, speed
+            ));
+        }
+        self.game_properties.lock().unwrap().game_speed = speed;
+
+        Ok(())
+    }
+
+    fn next_timing_mode(&mut self) {
+        let timing_mode = self.get_timing_mode().next();
+        self.set_timing_mode(timing_mode);
+    }
+
+    pub fn set_timing_mode(&mut self, timing_mode: TimingMode) {
+        self.game_properties.lock().unwrap().timing_mode = timing_mode;
+    }
+
+    pub fn get_timing_mode(&mut self) -> TimingMode {
+        self.game_properties.lock().unwrap().timing_mode
+    }
+
+    pub fn set_rates(&mut self, timer_rate: f64, instruction_rate: f64) {
+        let mut properties = self.game_properties.lock().unwrap();
+        properties.timer_rate = timer_rate;
+        properties.instruction_rate = instruction_rate;
+    }
+
+    fn next_quirk_profile(&mut self) {
+        let profile = self.quirks.lock().unwrap().profile.next();
+        self.set_quirk_profile(profile);
+    }
+
+    pub fn set_quirk_profile(&mut self, profile: QuirkProfile) {
+        self.quirks.lock().unwrap().set_profile(profile);
+        self.game_properties.lock().unwrap().quirk_profile = profile;
+    }
+
+    fn next_control_page(&mut self) {
+        self.game_properties.lock().unwrap().control_page += 1;
+    }
+
+    pub fn set_palette(&mut self, palette: [u32; PALETTE_SIZE]) {
+        self.game_properties.lock().unwrap().palette = palette;
+    }
+
+    pub fn request_save_state(&mut self, slot: u8) {
+        self.state_manager
+            .lock()
+            .unwrap()
+            .update_state(ProgramState::SaveState(slot));
+    }
+
+    pub fn request_load_state(&mut self, slot: u8) {
+        self.state_manager
+            .lock()
+            .unwrap()
+            .update_state(ProgramState::LoadState(slot));
+    }
+
+    pub fn start_rewind(&mut self) {
+        self.state_manager
+            .lock()
+            .unwrap()
+            .update_state(ProgramState::Rewind);
+    }
+
+    pub fn stop_rewind(&mut self) {
+        self.state_manager.lock().unwrap().stop_rewind();
+    }
+
+    pub fn write_save_state(&mut self, slot: u8, state: &MachineState) {
+        let rom_hash = MachineState::rom_hash(&self.file_manager.get_file_content());
+        match self
+            .file_manager
+            .write_save_state(slot, &state.to_bytes(rom_hash))
+        {
+            Ok(path) => println!("Saved state to {}", path),
+            Err(error) => println!("{}", error),
+        }
+    }
+
+    pub fn read_save_state(&mut self, slot: u8) -> result::Result<MachineState, String> {
+        let rom_hash = MachineState::rom_hash(&self.file_manager.get_file_content());
+        let bytes = self.file_manager.read_save_state(slot)?;
+        let state = MachineState::from_bytes(&bytes, rom_hash)?;
+        println!("Loaded state from slot {}", slot);
+
+        Ok(state)
+    }
+
+    pub fn apply_save_state(&mut self, state: &MachineState) {
+        let mut properties = self.game_properties.lock().unwrap();
+        properties.game_speed = state.speed;
+        properties.timing_mode = state.timing_mode;
+        properties.quirk_profile = state.quirks.profile;
+    }
+
     fn restart_program(&mut self) {
         self.state_manager
             .lock()
@@ -92,17 +205,21 @@ impl ProgramManager {
     }
 
     fn load_file(&mut self) {
-        if self.file_manager.load_file().is_ok() {
-            self.state_manager
-                .lock()
-                .unwrap()
-                .update_state(ProgramState::NewProgram);
-            self.update_game_properties();
-        } else {
-            self.state_manager
-                .lock()
-                .unwrap()
-                .update_state(ProgramState::Idle);
+        match self.file_manager.load_file() {
+            Ok(()) => {
+                self.state_manager
+                    .lock()
+                    .unwrap()
+                    .update_state(ProgramState::NewProgram);
+                self.update_game_properties();
+            }
+            Err(error) => {
+                println!("Error: Failed to load file: {}", error);
+                self.state_manager
+                    .lock()
+                    .unwrap()
+                    .update_state(ProgramState::Idle);
+            }
         }
     }
 
@@ -111,12 +228,15 @@ impl ProgramManager {
     }
 
     pub fn new_file(&mut self, file_name: &str) {
-        if self.file_manager.load_file_if_possible(file_name).is_ok() {
-            self.state_manager
-                .lock()
-                .unwrap()
-                .update_state(ProgramState::NewProgram);
-            self.update_game_properties();
+        match self.file_manager.load_file_if_possible(file_name) {
+            Ok(()) => {
+                self.state_manager
+                    .lock()
+                    .unwrap()
+                    .update_state(ProgramState::NewProgram);
+                self.update_game_properties();
+            }
+            Err(error) => println!("Error: Failed to load {}: {}", file_name, error),
         }
     }
 
@@ -142,4 +262,8 @@ impl ProgramManager {
     pub fn get_file_content(&mut self) -> Vec<u8> {
         self.file_manager.get_file_content()
     }
+
+    pub fn take_symbols(&mut self) -> Option<SymbolTable> {
+        self.file_manager.take_symbols()
+    }
 }
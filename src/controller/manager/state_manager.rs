@@ -72,8 +72,12 @@ impl StateManager {
             ProgramState::Restart => self.restart(),
             ProgramState::Debug(DebugState::Breakpoint) => self.breakpoint(),
             ProgramState::Debug(DebugState::Step) => self.step(),
+            ProgramState::Debug(DebugState::StepBack) => self.step_back(),
             ProgramState::Quit => self.quit(),
             ProgramState::Stopped => self.stop(),
+            ProgramState::SaveState(_) | ProgramState::LoadState(_) | ProgramState::Rewind => {
+                self.snapshot(state)
+            }
             _ => {}
         }
     }
@@ -83,10 +87,14 @@ impl StateManager {
         let program_state = self.states.lock().unwrap().program_state;
         match (program_state, finished_state, cpu_state) {
             (_, ProgramState::NewProgram, _) | (_, ProgramState::Restart, _) => self.running(),
+            (_, ProgramState::SaveState(_), _) | (_, ProgramState::LoadState(_), _) => {
+                self.running()
+            }
             (ProgramState::NewProgram, _, _) => self.new_program(),
             (ProgramState::Restart, _, _) => self.restart(),
             (_, _, CpuState::Stopped) => self.failed_game(),
-            (_, ProgramState::Debug(DebugState::Step), CpuState::Running) => self.stop(),
+            (_, ProgramState::Debug(DebugState::Step), CpuState::Running)
+            | (_, ProgramState::Debug(DebugState::StepBack), CpuState::Running) => self.stop(),
             _ => {}
         }
     }
@@ -119,6 +127,27 @@ impl StateManager {
         }
     }
 
+    fn snapshot(&mut self, state: ProgramState) {
+        let mut states = self.states.lock().unwrap();
+        match states.program_state {
+            ProgramState::Running | ProgramState::Stopped => states.program_state = state,
+            _ => {}
+        }
+    }
+
+    pub fn stop_rewind(&mut self) {
+        if self.get_state() == ProgramState::Rewind {
+            self.running();
+        }
+    }
+
+    fn step_back(&mut self) {
+        let mut states = self.states.lock().unwrap();
+        if states.game_state == GameState::Stopped && states.debug_state == DebugState::Enabled {
+            states.program_state = ProgramState::Debug(DebugState::StepBack);
+        }
+    }
+
     fn failed_game(&mut self) {
         let mut states = self.states.lock().unwrap();
         states.debug_state = DebugState::Disabled;
@@ -1,7 +1,13 @@
+mod assembler;
+mod disassembler;
+#[cfg(feature = "gui")]
 mod emulator;
 mod manager;
 mod utils;
 
+pub use self::assembler::*;
+pub use self::disassembler::*;
+#[cfg(feature = "gui")]
 pub use self::emulator::Emulator;
 pub use self::manager::*;
 pub use self::utils::*;
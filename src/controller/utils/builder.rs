@@ -1,12 +1,16 @@
-use crate::controller::{DebugManager, Emulator, FileManager, ProgramManager, StateManager};
+use crate::controller::{
+    DebugManager, Emulator, FileManager, GdbServer, LaunchOptions, ProgramManager, StateManager,
+    TraceLogger, TRACE_PATH,
+};
 use crate::model::{
-    Cpu, DebugProperties, DebugPropertiesAccess, GameProperties, GamePropertiesAccess, Keypad,
-    Memory, MemoryAccess, States, StatesAccess,
+    BreakpointSpec, Chip8, DebugProperties, DebugPropertiesAccess, GameProperties,
+    GamePropertiesAccess, Keypad, Memory, MemoryAccess, Quirks, RewindBuffer, States, StatesAccess,
+    Watchpoint,
 };
 
 use crate::view::{
-    BreakPointDisplay, DisplayManager, GameDisplay, InfoDisplay, InputChecker, MemoryDisplay,
-    OpcodeDisplay, SoundManager, StackDisplay, View,
+    BreakPointDisplay, DebugConsole, DisplayManager, GameDisplay, InfoDisplay, InputChecker,
+    MemoryDisplay, OpcodeDisplay, SoundManager, StackDisplay, View,
 };
 use std::cell::RefCell;
 use std::{
@@ -34,7 +38,7 @@ impl Builder {
         Builder {}
     }
 
-    pub fn build_emulator(&mut self, file_path: String) -> Emulator {
+    pub fn build_emulator(&mut self, options: LaunchOptions) -> Emulator {
         let game_properties = self.package_arc_mutex(GameProperties::new());
         let game_properties_access =
             self.package_arc_mutex(GamePropertiesAccess::new(Arc::clone(&game_properties)));
@@ -47,28 +51,109 @@ impl Builder {
         let states_access = self.package_arc_mutex(StatesAccess::new(Arc::clone(&states)));
         let state_manager = self.package_arc_mutex(StateManager::new(Arc::clone(&states)));
 
-        let data_ref = self.package_arc_mutex(Memory::new());
+        let data_ref = match options.seed {
+            Some(seed) => self.package_arc_mutex(Memory::with_seed(seed)),
+            None => self.package_arc_mutex(Memory::new()),
+        };
         let new_keypad = self.package_arc_mutex(Keypad::new());
+        let quirks = self.package_arc_mutex(Quirks::default());
 
-        let file_manager = FileManager::new(file_path);
+        let mut file_manager = FileManager::new(options.file_path.clone());
+        file_manager.set_symbols_path(options.symbols_path.clone());
         let access = self.package_arc_mutex(MemoryAccess::new(Arc::clone(&data_ref)));
         let program_manager = self.package_arc_mutex(ProgramManager::new(
             file_manager,
             Arc::clone(&state_manager),
             Arc::clone(&access),
             Arc::clone(&game_properties),
+            Arc::clone(&quirks),
         ));
+        program_manager
+            .lock()
+            .unwrap()
+            .set_quirk_profile(options.quirk_profile);
+        program_manager.lock().unwrap().set_palette(options.palette);
+        program_manager
+            .lock()
+            .unwrap()
+            .set_timing_mode(options.timing_mode);
+        if let Err(error) = program_manager
+            .lock()
+            .unwrap()
+            .set_speed(options.instructions_per_frame)
+        {
+            println!("{}", error);
+        }
+
+        let mut trace_logger = TraceLogger::new(
+            options
+                .trace_path
+                .clone()
+                .unwrap_or_else(|| TRACE_PATH.to_string()),
+            options.trace_size,
+        );
+        trace_logger.set_range(options.trace_range);
+        if options.trace_path.is_some() {
+            if let Err(error) = trace_logger.set_enabled(true) {
+                println!("{}", error);
+            }
+        }
+
+        let trace_logger = self.package_arc_mutex(trace_logger);
 
         let debug_manager = self.package_arc_mutex(DebugManager::new(
             Arc::clone(&access),
             Arc::clone(&state_manager),
             Arc::clone(&debug_properties),
+            trace_logger,
         ));
 
-        let cpu = Cpu::new(
-            Arc::clone(&new_keypad),
+        // The program is loaded here so breakpoints can name its symbols
+        program_manager.lock().unwrap().initialize();
+        if let Some(symbols) = program_manager.lock().unwrap().take_symbols() {
+            debug_manager.lock().unwrap().set_symbols(symbols);
+        }
+
+        let symbols = debug_properties_access.lock().unwrap().get_symbols();
+        for spec in options.breakpoints.iter() {
+            let result = BreakpointSpec::parse(spec, &symbols)
+                .and_then(|spec| debug_manager.lock().unwrap().set_breakpoint(&spec));
+            if let Err(error) = result {
+                println!("{}", error);
+            }
+        }
+        for watchpoint in options.watchpoints.iter() {
+            match Watchpoint::parse(watchpoint, &symbols) {
+                Ok(watchpoint) => debug_manager.lock().unwrap().set_watchpoint(watchpoint),
+                Err(error) => println!("{}", error),
+            }
+        }
+
+        if let Some(port) = options.gdb_port {
+            GdbServer::new(
+                port,
+                Arc::clone(&debug_manager),
+                Arc::clone(&state_manager),
+                Arc::clone(&access),
+            )
+            .start();
+        }
+
+        if options.debug_console {
+            DebugConsole::new(
+                Arc::clone(&debug_manager),
+                Arc::clone(&state_manager),
+                Arc::clone(&access),
+                Arc::clone(&debug_properties_access),
+            )
+            .start();
+        }
+
+        let chip8 = Chip8::with_shared_state(
             Arc::clone(&data_ref),
+            Arc::clone(&new_keypad),
             Arc::clone(&state_manager),
+            Arc::clone(&quirks),
         );
         let (audio_sender, audio_receiver) = channel();
 
@@ -84,12 +169,13 @@ impl Builder {
         );
 
         Emulator::new(
-            cpu,
+            chip8,
             Arc::clone(&program_manager),
             Arc::clone(&debug_manager),
             Arc::clone(&state_manager),
             view,
             audio_sender,
+            RewindBuffer::new(options.rewind_budget),
         )
     }
 
@@ -101,18 +187,26 @@ impl Builder {
         debug_properties_access: &Arc<Mutex<DebugPropertiesAccess>>,
         states_access: &Arc<Mutex<StatesAccess>>,
     ) {
-        let game_display = GameDisplay::new(Arc::clone(&mem_access));
-        let info_display =
-            InfoDisplay::new(Arc::clone(&properties_access), Arc::clone(&states_access));
-        let stack_display = StackDisplay::new(Arc::clone(&mem_access));
-        let memory_display = MemoryDisplay::new(Arc::clone(&mem_access));
+        let game_display =
+            GameDisplay::new(Arc::clone(&mem_access), Arc::clone(&properties_access));
+        let info_display = InfoDisplay::new(
+            Arc::clone(&properties_access),
+            Arc::clone(&states_access),
+            Arc::clone(debug_properties_access),
+            Arc::clone(&mem_access),
+        );
+        let stack_display =
+            StackDisplay::new(Arc::clone(&mem_access), Arc::clone(debug_properties_access));
+        let memory_display =
+            MemoryDisplay::new(Arc::clone(&mem_access), Arc::clone(debug_properties_access));
         let opcode_display = OpcodeDisplay::new(
             Arc::clone(&mem_access),
             Arc::clone(&properties_access),
             Arc::clone(&debug_properties_access),
             Arc::clone(&states_access),
         );
-        let breakpoint_display = BreakPointDisplay::new(Arc::clone(debug_properties_access));
+        let breakpoint_display =
+            BreakPointDisplay::new(Arc::clone(debug_properties_access), Arc::clone(&mem_access));
 
         display_manager.add_display(Box::new(game_display));
         display_manager.add_display(Box::new(info_display));
@@ -1,10 +1,11 @@
-use crate::defines::memory_constants::MAX_PROGRAM_SIZE;
+use crate::controller::{Assembly, Disassembler, OctoAssembler, OCTO_EXTENSION};
+use crate::defines::{memory_constants::MAX_PROGRAM_SIZE, InstructionSet};
 use crate::edit;
-use crate::view::Disassembler;
+use crate::model::SymbolTable;
 use native_dialog::FileDialog;
 
 use std::{
-    fs::{metadata, File},
+    fs::{create_dir_all, metadata, File},
     io,
     io::{BufWriter, Read, Write},
     path::Path,
@@ -13,7 +14,11 @@ use std::{
 };
 
 pub const MEMORY_DUMP_PATH: &str = "TempFiles/Memory_Content.bin";
-pub const FILE_EDITOR_PATH: &str = "TempFiles/Game_Code.ch8";
+pub const FILE_EDITOR_PATH: &str = "TempFiles/Game_Code.8o";
+pub const SAVE_STATE_DIRECTORY: &str = "SaveStates";
+pub const SAVE_STATE_EXTENSION: &str = "c8s";
+pub const PROFILE_REPORT_PATH: &str = "TempFiles/Profile.csv";
+pub const SYMBOL_EXTENSION: &str = "sym";
 
 #[derive(Default, Clone)]
 pub struct FileInfo {
@@ -25,6 +30,9 @@ pub struct FileManager {
     filecontent: Vec<u8>,
     file_path: String,
     file_info: FileInfo,
+    symbols: SymbolTable,
+    symbols_path: Option<String>,
+    symbols_changed: bool,
 }
 
 impl FileManager {
@@ -33,40 +41,127 @@ impl FileManager {
             filecontent: Vec::new(),
             file_path: path,
             file_info: FileInfo::default(),
+            symbols: SymbolTable::new(),
+            symbols_path: None,
+            symbols_changed: false,
         }
     }
 
+    pub fn set_symbols_path(&mut self, path: Option<String>) {
+        self.symbols_path = path;
+    }
+
     pub fn load_file(&mut self) -> io::Result<()> {
-        let mut file = File::open(self.file_path.clone())?;
-        let meta_data = metadata(self.file_path.clone())?;
+        let (buffer, labels) = if self.is_octo_source() {
+            let assembly = self.assemble_file()?;
+            (
+                assembly.rom,
+                Some(SymbolTable::from_labels(&assembly.labels)),
+            )
+        } else {
+            (self.read_binary_file()?, None)
+        };
+        self.symbols = self.find_symbols(labels);
+        self.symbols_changed = true;
 
-        assert!(meta_data.len() < MAX_PROGRAM_SIZE as u64);
         self.file_info.file_name = Path::new(&self.file_path)
             .file_name()
             .unwrap()
             .to_str()
             .unwrap()
             .to_string();
-        let file_size = meta_data.len();
-        let mut buffer = vec![0; file_size as usize];
-        file.read_exact(&mut buffer)?;
+        self.file_info.file_size = buffer.len() as u64;
         self.filecontent = buffer;
-        self.file_info.file_size = file_size;
 
         Ok(())
     }
 
+    fn read_binary_file(&mut self) -> io::Result<Vec<u8>> {
+        let mut file = File::open(self.file_path.clone())?;
+        let meta_data = metadata(self.file_path.clone())?;
+
+        assert!(meta_data.len() < MAX_PROGRAM_SIZE as u64);
+        let mut buffer = vec![0; meta_data.len() as usize];
+        file.read_exact(&mut buffer)?;
+
+        Ok(buffer)
+    }
+
+    fn assemble_file(&mut self) -> io::Result<Assembly> {
+        let mut source = String::new();
+        File::open(self.file_path.clone())?.read_to_string(&mut source)?;
+
+        match OctoAssembler::assemble(&source) {
+            Ok(assembly) => Ok(assembly),
+            Err(error) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                error.to_string(),
+            )),
+        }
+    }
+
+    // An explicit symbol file wins over the labels of an assembled program,
+    // which win over a symbol file named like the program
+    fn find_symbols(&self, labels: Option<SymbolTable>) -> SymbolTable {
+        let sibling = Path::new(&self.file_path).with_extension(SYMBOL_EXTENSION);
+        let path = match (self.symbols_path.as_ref(), labels) {
+            (Some(path), _) => path.clone(),
+            (None, Some(labels)) => return labels,
+            (None, None) if sibling.is_file() => sibling.to_string_lossy().to_string(),
+            (None, None) => return SymbolTable::new(),
+        };
+
+        match FileManager::read_symbols(&path) {
+            Ok(symbols) => {
+                println!("Loaded {} symbols from {}", symbols.len(), path);
+                symbols
+            }
+            Err(error) => {
+                println!("{}", error);
+                SymbolTable::new()
+            }
+        }
+    }
+
+    pub fn read_symbols(path: &str) -> result::Result<SymbolTable, String> {
+        let mut text = String::new();
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut text))
+            .map_err(|error| format!("Error: Failed to read {}: {}", path, error))?;
+
+        SymbolTable::parse(&text).map_err(|error| format!("{} in {}", error, path))
+    }
+
+    pub fn take_symbols(&mut self) -> Option<SymbolTable> {
+        if !self.symbols_changed {
+            return None;
+        }
+        self.symbols_changed = false;
+
+        Some(self.symbols.clone())
+    }
+
+    fn is_octo_source(&self) -> bool {
+        Path::new(&self.file_path)
+            .extension()
+            .is_some_and(|extension| extension == OCTO_EXTENSION)
+    }
+
     pub fn load_file_if_possible(&mut self, file_path: &str) -> io::Result<()> {
-        let old_file_name = self.file_info.file_name.clone();
+        let old_file_info = self.file_info.clone();
+        let old_file_path = self.file_path.clone();
         let old_file_content = self.filecontent.clone();
+        let old_symbols_path = self.symbols_path.take();
         self.filecontent = vec![0; MAX_PROGRAM_SIZE];
 
         self.file_path = String::from(file_path);
         let success = self.load_file();
 
         if success.is_err() {
-            self.file_info.file_name = old_file_name;
+            self.file_info = old_file_info;
+            self.file_path = old_file_path;
             self.filecontent = old_file_content;
+            self.symbols_path = old_symbols_path;
         }
 
         success
@@ -96,26 +191,77 @@ impl FileManager {
         println!("File content:\n{}", editable); */
     }
 
-    pub fn open_editor(&mut self) {
-        println!("Open editor");
-        let file = File::create(FILE_EDITOR_PATH).expect("Unable to create file");
-        let mut file = BufWriter::new(file);
-        let mut converted_code = Disassembler::convert_and_disassemble_list(&self.filecontent);
-        println!("first line:   {}", converted_code[0]);
-        for iter in converted_code.iter_mut() {
-            (*iter).push('\n');
-            file.write_all(&iter.as_str().as_bytes())
-                .expect("failed to write line");
+    pub fn open_editor(&mut self) -> result::Result<String, String> {
+        let path = if self.is_octo_source() {
+            self.file_path.clone()
+        } else {
+            let source =
+                Disassembler::octo_source(&self.filecontent, &self.symbols, InstructionSet::XoChip)
+                    .join("\n");
+            FileManager::write_report(FILE_EDITOR_PATH, &source)?;
+            FILE_EDITOR_PATH.to_string()
+        };
+
+        println!("Open editor: {}", path);
+        let editor =
+            edit::get_editor().map_err(|error| format!("Error: No editor found: {}", error))?;
+        let status = Command::new(editor)
+            .arg(&path)
+            .status()
+            .map_err(|error| format!("Error: Failed to start the editor: {}", error))?;
+        if !status.success() {
+            return Err(format!("Error: The editor exited with {}", status));
         }
 
-        let editor = edit::get_editor().unwrap();
+        Ok(path)
+    }
 
-        File::create(&FILE_EDITOR_PATH).expect("Could not create file");
+    pub fn write_save_state(&mut self, slot: u8, bytes: &[u8]) -> result::Result<String, String> {
+        let path = self.save_state_path(slot);
+        create_dir_all(SAVE_STATE_DIRECTORY).map_err(|error| {
+            format!(
+                "Error: Failed to create {}: {}",
+                SAVE_STATE_DIRECTORY, error
+            )
+        })?;
+        let mut file = File::create(&path)
+            .map_err(|error| format!("Error: Failed to create {}: {}", path, error))?;
+        file.write_all(bytes)
+            .map_err(|error| format!("Error: Failed to write {}: {}", path, error))?;
 
-        Command::new(editor)
-            .arg(FILE_EDITOR_PATH)
-            .status()
-            .expect("Something went wrong");
+        Ok(path)
+    }
+
+    pub fn write_report(path: &str, content: &str) -> result::Result<(), String> {
+        if let Some(directory) = Path::new(path).parent() {
+            create_dir_all(directory).map_err(|error| {
+                format!("Error: Failed to create {}: {}", directory.display(), error)
+            })?;
+        }
+        let mut file = File::create(path)
+            .map_err(|error| format!("Error: Failed to create {}: {}", path, error))?;
+        file.write_all(content.as_bytes())
+            .map_err(|error| format!("Error: Failed to write {}: {}", path, error))?;
+
+        Ok(())
+    }
+
+    pub fn read_save_state(&mut self, slot: u8) -> result::Result<Vec<u8>, String> {
+        let path = self.save_state_path(slot);
+        let mut file = File::open(&path)
+            .map_err(|_| format!("Error: No save state in slot {} ({})", slot, path))?;
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer)
+            .map_err(|error| format!("Error: Failed to read {}: {}", path, error))?;
+
+        Ok(buffer)
+    }
+
+    fn save_state_path(&self, slot: u8) -> String {
+        format!(
+            "{}/{}.slot{}.{}",
+            SAVE_STATE_DIRECTORY, self.file_info.file_name, slot, SAVE_STATE_EXTENSION
+        )
     }
 
     pub fn get_file_content(&mut self) -> Vec<u8> {
@@ -0,0 +1,532 @@
+use crate::controller::{
+    decode_hex, frame_packet, take_packet, DebugManager, Packet, StateManager, INTERRUPT,
+};
+use crate::defines::{
+    memory_constants::VARIABLES_COUNT, DebugState, GameState, MemoryOperation, ProgramState,
+    Register, WatchKind,
+};
+use crate::model::{parse_number, BreakpointSpec, MemoryAccess, WatchHit, Watchpoint};
+
+use std::{
+    io,
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+const REGISTER_INDEX: usize = VARIABLES_COUNT;
+const REGISTER_PC: usize = VARIABLES_COUNT + 1;
+const REGISTER_SP: usize = VARIABLES_COUNT + 2;
+const REGISTER_DT: usize = VARIABLES_COUNT + 3;
+const REGISTER_ST: usize = VARIABLES_COUNT + 4;
+const POLL_INTERVAL: Duration = Duration::from_millis(2);
+
+const TARGET_XML: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE target SYSTEM "gdb-target.dtd">
+<target version="1.0">
+  <feature name="org.chip8.core">
+    <reg name="v0" bitsize="8" type="uint8" regnum="0"/>
+    <reg name="v1" bitsize="8" type="uint8"/>
+    <reg name="v2" bitsize="8" type="uint8"/>
+    <reg name="v3" bitsize="8" type="uint8"/>
+    <reg name="v4" bitsize="8" type="uint8"/>
+    <reg name="v5" bitsize="8" type="uint8"/>
+    <reg name="v6" bitsize="8" type="uint8"/>
+    <reg name="v7" bitsize="8" type="uint8"/>
+    <reg name="v8" bitsize="8" type="uint8"/>
+    <reg name="v9" bitsize="8" type="uint8"/>
+    <reg name="va" bitsize="8" type="uint8"/>
+    <reg name="vb" bitsize="8" type="uint8"/>
+    <reg name="vc" bitsize="8" type="uint8"/>
+    <reg name="vd" bitsize="8" type="uint8"/>
+    <reg name="ve" bitsize="8" type="uint8"/>
+    <reg name="vf" bitsize="8" type="uint8"/>
+    <reg name="i" bitsize="16" type="data_ptr"/>
+    <reg name="pc" bitsize="16" type="code_ptr"/>
+    <reg name="sp" bitsize="8" type="uint8"/>
+    <reg name="dt" bitsize="8" type="uint8"/>
+    <reg name="st" bitsize="8" type="uint8"/>
+  </feature>
+</target>
+"#;
+
+pub struct GdbServer {
+    port: u16,
+    debug_manager: Arc<Mutex<DebugManager>>,
+    state_manager: Arc<Mutex<StateManager>>,
+    memory_access: Arc<Mutex<MemoryAccess>>,
+    pending: Vec<u8>,
+    no_ack: bool,
+}
+
+impl GdbServer {
+    pub fn new(
+        new_port: u16,
+        new_debug_manager: Arc<Mutex<DebugManager>>,
+        new_state_manager: Arc<Mutex<StateManager>>,
+        new_memory_access: Arc<Mutex<MemoryAccess>>,
+    ) -> GdbServer {
+        GdbServer {
+            port: new_port,
+            debug_manager: new_debug_manager,
+            state_manager: new_state_manager,
+            memory_access: new_memory_access,
+            pending: Vec::new(),
+            no_ack: false,
+        }
+    }
+
+    pub fn start(mut self) {
+        thread::spawn(move || self.listen());
+    }
+
+    fn listen(&mut self) {
+        let listener = match TcpListener::bind(("127.0.0.1", self.port)) {
+            Ok(listener) => listener,
+            Err(error) => {
+                println!(
+                    "Error: Failed to start the GDB server on port {}: {}",
+                    self.port, error
+                );
+                return;
+            }
+        };
+        println!("GDB server listening on 127.0.0.1:{}", self.port);
+
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    println!("GDB client connected");
+                    self.pending.clear();
+                    self.no_ack = false;
+                    self.halt();
+                    if let Err(error) = self.serve(stream) {
+                        println!("Error: GDB connection failed: {}", error);
+                    }
+                    println!("GDB client disconnected");
+                }
+                Err(error) => println!("Error: GDB connection failed: {}", error),
+            }
+        }
+    }
+
+    fn serve(&mut self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_nodelay(true)?;
+        loop {
+            let packet = match self.read_packet(&mut stream)? {
+                Some(packet) => packet,
+                None => return Ok(()),
+            };
+
+            match packet {
+                Packet::Interrupt => {
+                    self.halt();
+                    self.send(&mut stream, "S02")?;
+                }
+                Packet::Command(command) | Packet::Corrupt(command) => {
+                    if !self.no_ack {
+                        stream.write_all(b"+")?;
+                    }
+                    match self.handle(&command, &mut stream)? {
+                        Some(reply) => self.send(&mut stream, &reply)?,
+                        None => return Ok(()),
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle(&mut self, command: &str, stream: &mut TcpStream) -> io::Result<Option<String>> {
+        let arguments = command.get(1..).unwrap_or("");
+        let reply = match command.chars().next() {
+            Some('?') => "S05".to_string(),
+            Some('g') => self.read_registers(),
+            Some('G') => self.write_registers(arguments),
+            Some('p') => self.read_register(arguments),
+            Some('P') => self.write_register(arguments),
+            Some('m') => self.read_memory(arguments),
+            Some('M') => self.write_memory(arguments),
+            Some('c') => self.resume(stream)?,
+            Some('s') => self.step(),
+            Some('Z') => self.insert_point(arguments),
+            Some('z') => self.remove_point(arguments),
+            Some('H') => "OK".to_string(),
+            Some('q') => self.query(command),
+            Some('Q') if command == "QStartNoAckMode" => {
+                self.no_ack = true;
+                "OK".to_string()
+            }
+            Some('D') => {
+                self.send(stream, "OK")?;
+                return Ok(None);
+            }
+            Some('k') => return Ok(None),
+            _ => String::new(),
+        };
+
+        Ok(Some(reply))
+    }
+
+    fn query(&mut self, command: &str) -> String {
+        if command.starts_with("qSupported") {
+            return "PacketSize=4000;qXfer:features:read+;QStartNoAckMode+".to_string();
+        }
+        if let Some(arguments) = command.strip_prefix("qXfer:features:read:target.xml:") {
+            return GdbServer::read_target_xml(arguments);
+        }
+
+        match command {
+            "qAttached" => "1".to_string(),
+            "qC" => "QC1".to_string(),
+            "qfThreadInfo" => "m1".to_string(),
+            "qsThreadInfo" => "l".to_string(),
+            _ => String::new(),
+        }
+    }
+
+    fn read_target_xml(arguments: &str) -> String {
+        match GdbServer::parse_pair(arguments) {
+            Some((offset, length)) => {
+                let data = TARGET_XML.as_bytes();
+                let start = offset.min(data.len());
+                let end = (start + length).min(data.len());
+                let prefix = if end < data.len() { "m" } else { "l" };
+                format!("{}{}", prefix, String::from_utf8_lossy(&data[start..end]))
+            }
+            None => "E01".to_string(),
+        }
+    }
+
+    fn read_registers(&mut self) -> String {
+        (0..=REGISTER_ST)
+            .filter_map(|register| self.register_value(register))
+            .collect()
+    }
+
+    fn read_register(&mut self, arguments: &str) -> String {
+        usize::from_str_radix(arguments, 16)
+            .ok()
+            .and_then(|register| self.register_value(register))
+            .unwrap_or_else(|| "E01".to_string())
+    }
+
+    fn register_value(&mut self, number: usize) -> Option<String> {
+        let register = GdbServer::register(number)?;
+        let value = self.memory_access.lock().unwrap().get_register(register)?;
+        if GdbServer::register_size(register) == 2 {
+            return Some(GdbServer::encode_u16(value as u16));
+        }
+
+        Some(format!("{:02x}", value))
+    }
+
+    fn write_registers(&mut self, arguments: &str) -> String {
+        let bytes = match decode_hex(arguments) {
+            Some(bytes) => bytes,
+            None => return "E01".to_string(),
+        };
+
+        let mut offset = 0;
+        for number in 0..=REGISTER_ST {
+            let register = GdbServer::register(number).unwrap();
+            let size = GdbServer::register_size(register);
+            if offset + size > bytes.len() {
+                break;
+            }
+            let value = GdbServer::decode_value(&bytes[offset..offset + size]);
+            if !self
+                .memory_access
+                .lock()
+                .unwrap()
+                .set_register(register, value)
+            {
+                return "E01".to_string();
+            }
+            offset += size;
+        }
+
+        "OK".to_string()
+    }
+
+    fn write_register(&mut self, arguments: &str) -> String {
+        let mut parts = arguments.splitn(2, '=');
+        let register = parts
+            .next()
+            .and_then(|number| usize::from_str_radix(number, 16).ok())
+            .and_then(GdbServer::register);
+        let bytes = parts.next().and_then(decode_hex);
+
+        match (register, bytes) {
+            (Some(register), Some(ref bytes))
+                if bytes.len() == GdbServer::register_size(register) =>
+            {
+                if self
+                    .memory_access
+                    .lock()
+                    .unwrap()
+                    .set_register(register, GdbServer::decode_value(bytes))
+                {
+                    "OK".to_string()
+                } else {
+                    "E01".to_string()
+                }
+            }
+            _ => "E01".to_string(),
+        }
+    }
+
+    fn register(number: usize) -> Option<Register> {
+        match number {
+            REGISTER_INDEX => Some(Register::Index),
+            REGISTER_PC => Some(Register::ProgramCounter),
+            REGISTER_SP => Some(Register::StackPointer),
+            REGISTER_DT => Some(Register::DelayTimer),
+            REGISTER_ST => Some(Register::SoundTimer),
+            _ if number < VARIABLES_COUNT => Some(Register::Variable(number)),
+            _ => None,
+        }
+    }
+
+    fn register_size(register: Register) -> usize {
+        match register {
+            Register::Index | Register::ProgramCounter => 2,
+            _ => 1,
+        }
+    }
+
+    fn read_memory(&mut self, arguments: &str) -> String {
+        let (address, length) = match GdbServer::parse_pair(arguments) {
+            Some(pair) => pair,
+            None => return "E01".to_string(),
+        };
+
+        match self
+            .memory_access
+            .lock()
+            .unwrap()
+            .get_memory_range(address, length)
+        {
+            Some(bytes) => bytes.iter().map(|byte| format!("{:02x}", byte)).collect(),
+            None => "E01".to_string(),
+        }
+    }
+
+    fn write_memory(&mut self, arguments: &str) -> String {
+        let mut parts = arguments.splitn(2, ':');
+        let pair = parts.next().and_then(GdbServer::parse_pair);
+        let bytes = parts.next().and_then(decode_hex);
+
+        match (pair, bytes) {
+            (Some((address, length)), Some(ref bytes)) if bytes.len() == length => {
+                if self
+                    .memory_access
+                    .lock()
+                    .unwrap()
+                    .set_memory_range(address, bytes)
+                {
+                    "OK".to_string()
+                } else {
+                    "E01".to_string()
+                }
+            }
+            _ => "E01".to_string(),
+        }
+    }
+
+    fn step(&mut self) -> String {
+        self.state_manager
+            .lock()
+            .unwrap()
+            .update_state(ProgramState::Debug(DebugState::Step));
+        while self.state_manager.lock().unwrap().get_state()
+            == ProgramState::Debug(DebugState::Step)
+        {
+            thread::sleep(POLL_INTERVAL);
+        }
+
+        "S05".to_string()
+    }
+
+    fn resume(&mut self, stream: &mut TcpStream) -> io::Result<String> {
+        let last_watch_hit = self.debug_manager.lock().unwrap().get_last_watch_hit();
+        self.debug_manager.lock().unwrap().resume();
+
+        stream.set_read_timeout(Some(POLL_INTERVAL))?;
+        let reply = loop {
+            if self.poll_interrupt(stream)? {
+                self.halt();
+                break "S02".to_string();
+            }
+
+            let game_state = self.state_manager.lock().unwrap().get_game_state();
+            match game_state {
+                GameState::Running => {}
+                GameState::Failed => break "S04".to_string(),
+                _ => break self.stop_reply(last_watch_hit),
+            }
+        };
+        stream.set_read_timeout(None)?;
+
+        Ok(reply)
+    }
+
+    fn stop_reply(&mut self, last_watch_hit: Option<WatchHit>) -> String {
+        let watch_hit = self.debug_manager.lock().unwrap().get_last_watch_hit();
+        match watch_hit {
+            Some(hit) if watch_hit != last_watch_hit => {
+                let kind = match hit.event.operation {
+                    MemoryOperation::Read => "rwatch",
+                    MemoryOperation::Write => "watch",
+                };
+                format!("T05{}:{:x};", kind, hit.event.address)
+            }
+            _ => "S05".to_string(),
+        }
+    }
+
+    fn insert_point(&mut self, arguments: &str) -> String {
+        let (kind, address, length) = match GdbServer::parse_point(arguments) {
+            Some(point) => point,
+            None => return "E01".to_string(),
+        };
+        let mut debug_manager = self.debug_manager.lock().unwrap();
+
+        match GdbServer::watch_kind(kind) {
+            Some(watch_kind) => {
+                debug_manager.set_watchpoint(Watchpoint::new(
+                    address,
+                    address + length.max(1) - 1,
+                    watch_kind,
+                ));
+                "OK".to_string()
+            }
+            None if kind == 0 || kind == 1 => {
+                let spec = BreakpointSpec {
+                    address,
+                    condition: None,
+                    ignore_count: 0,
+                };
+                match debug_manager.set_breakpoint(&spec) {
+                    Ok(()) => "OK".to_string(),
+                    Err(_) => "E01".to_string(),
+                }
+            }
+            None => String::new(),
+        }
+    }
+
+    fn remove_point(&mut self, arguments: &str) -> String {
+        let (kind, address, length) = match GdbServer::parse_point(arguments) {
+            Some(point) => point,
+            None => return "E01".to_string(),
+        };
+        let mut debug_manager = self.debug_manager.lock().unwrap();
+
+        match GdbServer::watch_kind(kind) {
+            Some(watch_kind) => {
+                let end = address + length.max(1) - 1;
+                if let Some(index) = debug_manager.find_watchpoint(address, end, watch_kind) {
+                    debug_manager.remove_watchpoint(index);
+                }
+                "OK".to_string()
+            }
+            None if kind == 0 || kind == 1 => {
+                debug_manager.remove_breakpoint(address);
+                "OK".to_string()
+            }
+            None => String::new(),
+        }
+    }
+
+    fn watch_kind(kind: usize) -> Option<WatchKind> {
+        match kind {
+            2 => Some(WatchKind::Write),
+            3 => Some(WatchKind::Read),
+            4 => Some(WatchKind::ReadWrite),
+            _ => None,
+        }
+    }
+
+    fn halt(&mut self) {
+        self.debug_manager.lock().unwrap().pause();
+    }
+
+    fn read_packet(&mut self, stream: &mut TcpStream) -> io::Result<Option<Packet>> {
+        loop {
+            if let Some(packet) = self.parse_pending(stream)? {
+                return Ok(Some(packet));
+            }
+
+            let mut buffer = [0; 1024];
+            let count = stream.read(&mut buffer)?;
+            if count == 0 {
+                return Ok(None);
+            }
+            self.pending.extend_from_slice(&buffer[..count]);
+        }
+    }
+
+    fn poll_interrupt(&mut self, stream: &mut TcpStream) -> io::Result<bool> {
+        let mut buffer = [0; 1024];
+        match stream.read(&mut buffer) {
+            Ok(0) => return Ok(true),
+            Ok(count) => self.pending.extend_from_slice(&buffer[..count]),
+            Err(ref error)
+                if error.kind() == io::ErrorKind::WouldBlock
+                    || error.kind() == io::ErrorKind::TimedOut => {}
+            Err(error) => return Err(error),
+        }
+
+        match self.pending.iter().position(|byte| *byte == INTERRUPT) {
+            Some(position) => {
+                self.pending.remove(position);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn parse_pending(&mut self, stream: &mut TcpStream) -> io::Result<Option<Packet>> {
+        match take_packet(&mut self.pending) {
+            Some(Packet::Corrupt(_)) if !self.no_ack => {
+                stream.write_all(b"-")?;
+                Ok(None)
+            }
+            packet => Ok(packet),
+        }
+    }
+
+    fn send(&mut self, stream: &mut TcpStream, data: &str) -> io::Result<()> {
+        stream.write_all(frame_packet(data).as_bytes())
+    }
+
+    fn parse_pair(arguments: &str) -> Option<(usize, usize)> {
+        let mut parts = arguments.splitn(2, ',');
+        let first = usize::from_str_radix(parts.next()?, 16).ok()?;
+        let second = usize::from_str_radix(parts.next()?, 16).ok()?;
+
+        Some((first, second))
+    }
+
+    fn parse_point(arguments: &str) -> Option<(usize, usize, usize)> {
+        let mut parts = arguments.split(',');
+        let kind = parse_number(parts.next()?)?;
+        let address = usize::from_str_radix(parts.next()?, 16).ok()?;
+        let length = usize::from_str_radix(parts.next()?.split(';').next()?, 16).ok()?;
+
+        Some((kind, address, length))
+    }
+
+    fn decode_value(bytes: &[u8]) -> usize {
+        bytes
+            .iter()
+            .rev()
+            .fold(0, |value, byte| value << 8 | *byte as usize)
+    }
+
+    fn encode_u16(value: u16) -> String {
+        format!("{:02x}{:02x}", value & 0xFF, value >> 8)
+    }
+}
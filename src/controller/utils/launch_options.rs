@@ -0,0 +1,191 @@
+use crate::defines::{
+    game_constants::{
+        BASE_PROGRAM_SPEED, DEFAULT_PALETTE, DEFAULT_REWIND_BUDGET, DEFAULT_TRACE_SIZE,
+        PALETTE_SIZE,
+    },
+    QuirkProfile, TimingMode,
+};
+use crate::model::parse_range;
+
+use std::result;
+
+pub const USAGE: &str =
+    "Usage: chip8_rust <game_file> [--quirks vip|chip48|schip10|schip11|xochip] \
+     [--ipf <count>] [--timing flat|vip] [--palette RRGGBB,RRGGBB,RRGGBB,RRGGBB] [--seed <number>] [--rewind-memory <MiB>] \
+     [--break \"<address> [if <condition>] [ignore <count>]\"] \
+     [--watch \"<start>[-<end>] [r|w|rw]\"] [--gdb <port>] \
+     [--debug-console] [--trace <file>] [--trace-range <start>[-<end>]] \
+     [--trace-size <MiB>] [--symbols <file>]";
+
+#[derive(Clone)]
+pub struct LaunchOptions {
+    pub file_path: String,
+    pub quirk_profile: QuirkProfile,
+    pub instructions_per_frame: u64,
+    pub timing_mode: TimingMode,
+    pub palette: [u32; PALETTE_SIZE],
+    pub seed: Option<u64>,
+    pub rewind_budget: usize,
+    pub breakpoints: Vec<String>,
+    pub watchpoints: Vec<String>,
+    pub symbols_path: Option<String>,
+    pub gdb_port: Option<u16>,
+    pub debug_console: bool,
+    pub trace_path: Option<String>,
+    pub trace_range: Option<(usize, usize)>,
+    pub trace_size: usize,
+}
+
+impl LaunchOptions {
+    pub fn new(path: String) -> LaunchOptions {
+        LaunchOptions {
+            file_path: path,
+            quirk_profile: QuirkProfile::SChip11,
+            instructions_per_frame: BASE_PROGRAM_SPEED,
+            timing_mode: TimingMode::Flat,
+            palette: DEFAULT_PALETTE,
+            seed: None,
+            rewind_budget: DEFAULT_REWIND_BUDGET,
+            breakpoints: Vec::new(),
+            watchpoints: Vec::new(),
+            symbols_path: None,
+            gdb_port: None,
+            debug_console: false,
+            trace_path: None,
+            trace_range: None,
+            trace_size: DEFAULT_TRACE_SIZE,
+        }
+    }
+
+    pub fn from_args(args: &[String]) -> result::Result<LaunchOptions, String> {
+        let mut file_path: Option<String> = None;
+        let mut options = LaunchOptions::new(String::new());
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--quirks" => {
+                    let value = LaunchOptions::next_value(&mut iter, arg)?;
+                    options.quirk_profile = QuirkProfile::from_name(value)
+                        .ok_or(format!("Error: Unknown quirk profile: {}", value))?;
+                }
+                "--ipf" => {
+                    let value = LaunchOptions::next_value(&mut iter, arg)?;
+                    options.instructions_per_frame = LaunchOptions::parse_number(value)?;
+                }
+                "--timing" => {
+                    let value = LaunchOptions::next_value(&mut iter, arg)?;
+                    options.timing_mode = TimingMode::from_name(value)
+                        .ok_or(format!("Error: Unknown timing mode: {}", value))?;
+                }
+                "--palette" => {
+                    let value = LaunchOptions::next_value(&mut iter, arg)?;
+                    options.palette = LaunchOptions::parse_palette(value)?;
+                }
+                "--seed" => {
+                    let value = LaunchOptions::next_value(&mut iter, arg)?;
+                    options.seed = Some(LaunchOptions::parse_number(value)?);
+                }
+                "--rewind-memory" => {
+                    let value = LaunchOptions::next_value(&mut iter, arg)?;
+                    let budget = LaunchOptions::parse_mebibytes(value)?;
+                    if budget == 0 {
+                        return Err(
+                            "Error: The rewind memory must be at least 1 MiB to hold one frame"
+                                .to_string(),
+                        );
+                    }
+                    options.rewind_budget = budget;
+                }
+                "--break" => {
+                    let value = LaunchOptions::next_value(&mut iter, arg)?;
+                    options.breakpoints.push(value.clone());
+                }
+                "--watch" => {
+                    let value = LaunchOptions::next_value(&mut iter, arg)?;
+                    options.watchpoints.push(value.clone());
+                }
+                "--symbols" => {
+                    let value = LaunchOptions::next_value(&mut iter, arg)?;
+                    options.symbols_path = Some(value.clone());
+                }
+                "--gdb" => {
+                    let value = LaunchOptions::next_value(&mut iter, arg)?;
+                    options.gdb_port = Some(
+                        value
+                            .parse::<u16>()
+                            .map_err(|_| format!("Error: Invalid port: {}", value))?,
+                    );
+                }
+                "--debug-console" => options.debug_console = true,
+                "--trace" => {
+                    let value = LaunchOptions::next_value(&mut iter, arg)?;
+                    options.trace_path = Some(value.clone());
+                }
+                "--trace-range" => {
+                    let value = LaunchOptions::next_value(&mut iter, arg)?;
+                    options.trace_range = Some(
+                        parse_range(value)
+                            .ok_or(format!("Error: Invalid trace range: {}", value))?,
+                    );
+                }
+                "--trace-size" => {
+                    let value = LaunchOptions::next_value(&mut iter, arg)?;
+                    options.trace_size = LaunchOptions::parse_mebibytes(value)?;
+                }
+                _ if arg.starts_with("--") => {
+                    return Err(format!("Error: Unknown option: {}\n{}", arg, USAGE));
+                }
+                _ => file_path = Some(arg.clone()),
+            }
+        }
+
+        options.file_path = file_path.ok_or(format!("Error: No game file given\n{}", USAGE))?;
+
+        Ok(options)
+    }
+
+    fn parse_palette(value: &str) -> result::Result<[u32; PALETTE_SIZE], String> {
+        let colors: Vec<&str> = value.split(',').collect();
+        if colors.len() != PALETTE_SIZE {
+            return Err(format!(
+                "Error: The palette needs {} colors, got: {}",
+                PALETTE_SIZE, value
+            ));
+        }
+
+        let mut palette = [0; PALETTE_SIZE];
+        for (entry, color) in palette.iter_mut().zip(colors.iter()) {
+            *entry = u32::from_str_radix(color.trim().trim_start_matches('#'), 16)
+                .map_err(|_| format!("Error: Invalid palette color: {}", color))?;
+        }
+
+        Ok(palette)
+    }
+
+    fn parse_number(value: &str) -> result::Result<u64, String> {
+        let parsed = if value.starts_with("0x") || value.starts_with("0X") {
+            u64::from_str_radix(&value[2..], 16)
+        } else {
+            value.parse::<u64>()
+        };
+
+        parsed.map_err(|_| format!("Error: Invalid number: {}", value))
+    }
+
+    fn parse_mebibytes(value: &str) -> result::Result<usize, String> {
+        LaunchOptions::parse_number(value)?
+            .checked_mul(1024 * 1024)
+            .filter(|bytes| *bytes <= usize::MAX as u64)
+            .map(|bytes| bytes as usize)
+            .ok_or(format!("Error: {} MiB do not fit into memory", value))
+    }
+
+    fn next_value<'a, I>(iter: &mut I, option: &str) -> result::Result<&'a String, String>
+    where
+        I: Iterator<Item = &'a String>,
+    {
+        iter.next()
+            .ok_or(format!("Error: Missing value for option: {}", option))
+    }
+}
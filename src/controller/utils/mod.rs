@@ -1,10 +1,26 @@
 mod access_point;
+#[cfg(feature = "gui")]
 mod builder;
 mod error_handler;
+#[cfg(feature = "gui")]
 mod filemanager;
+#[cfg(feature = "gui")]
+mod gdb_server;
+mod launch_options;
+mod rsp_packet;
 mod time_manager;
+#[cfg(feature = "gui")]
+mod trace_logger;
 
+#[cfg(feature = "gui")]
 pub use self::builder::Builder;
 pub use self::error_handler::ErrorHandler;
-pub use self::filemanager::{FileInfo, FileManager};
-pub use self::time_manager::{TimeManager, TimeTo};
+#[cfg(feature = "gui")]
+pub use self::filemanager::{FileInfo, FileManager, PROFILE_REPORT_PATH};
+#[cfg(feature = "gui")]
+pub use self::gdb_server::GdbServer;
+pub use self::launch_options::{LaunchOptions, USAGE};
+pub use self::rsp_packet::{checksum, decode_hex, frame_packet, take_packet, Packet, INTERRUPT};
+pub use self::time_manager::{FrameClock, RateMeter, TimeManager, TimeTo, FRAME_RATE};
+#[cfg(feature = "gui")]
+pub use self::trace_logger::{TraceLogger, TraceStep, TRACE_PATH};
@@ -0,0 +1,126 @@
+pub const INTERRUPT: u8 = 0x03;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Packet {
+    Command(String),
+    Corrupt(String),
+    Interrupt,
+}
+
+// Takes the next "$<data>#<checksum>" packet or interrupt byte from the received
+// bytes. Bytes in front of it, like the acknowledgements of the client, are dropped,
+// an incomplete packet stays in the buffer until the rest arrives
+pub fn take_packet(pending: &mut Vec<u8>) -> Option<Packet> {
+    while let Some(&byte) = pending.first() {
+        match byte {
+            INTERRUPT => {
+                pending.remove(0);
+                return Some(Packet::Interrupt);
+            }
+            b'$' => break,
+            _ => {
+                pending.remove(0);
+            }
+        }
+    }
+
+    let end = match pending.iter().position(|byte| *byte == b'#') {
+        Some(end) if end + 2 < pending.len() => end,
+        _ => return None,
+    };
+
+    let packet: Vec<u8> = pending.drain(..end + 3).collect();
+    let data = &packet[1..end];
+    let command = String::from_utf8_lossy(data).into_owned();
+
+    if decode_hex(&String::from_utf8_lossy(&packet[end + 1..])) == Some(vec![checksum(data)]) {
+        Some(Packet::Command(command))
+    } else {
+        Some(Packet::Corrupt(command))
+    }
+}
+
+pub fn frame_packet(data: &str) -> String {
+    format!("${}#{:02x}", data, checksum(data.as_bytes()))
+}
+
+pub fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0, |sum: u8, byte| sum.wrapping_add(*byte))
+}
+
+pub fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    let bytes = text.as_bytes();
+    if !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+
+    bytes
+        .chunks(2)
+        .map(|pair| {
+            let high = (pair[0] as char).to_digit(16)?;
+            let low = (pair[1] as char).to_digit(16)?;
+            Some((high << 4 | low) as u8)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn takes_a_packet_with_a_valid_checksum() {
+        let mut pending = b"+$g#67".to_vec();
+
+        assert_eq!(take_packet(&mut pending), Some(Packet::Command("g".into())));
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn marks_a_wrong_checksum_as_corrupt() {
+        let mut pending = b"$g#00".to_vec();
+
+        assert_eq!(take_packet(&mut pending), Some(Packet::Corrupt("g".into())));
+    }
+
+    #[test]
+    fn waits_for_the_rest_of_a_packet() {
+        let mut pending = b"$m200,2#".to_vec();
+        assert_eq!(take_packet(&mut pending), None);
+
+        pending.extend_from_slice(b"5d$g#67");
+        assert_eq!(
+            take_packet(&mut pending),
+            Some(Packet::Command("m200,2".into()))
+        );
+        assert_eq!(take_packet(&mut pending), Some(Packet::Command("g".into())));
+    }
+
+    #[test]
+    fn takes_an_interrupt_before_a_packet() {
+        let mut pending = vec![INTERRUPT];
+        pending.extend_from_slice(b"$?#3f");
+
+        assert_eq!(take_packet(&mut pending), Some(Packet::Interrupt));
+        assert_eq!(take_packet(&mut pending), Some(Packet::Command("?".into())));
+    }
+
+    #[test]
+    fn frames_a_reply_with_its_checksum() {
+        assert_eq!(frame_packet("OK"), "$OK#9a");
+        assert_eq!(frame_packet(""), "$#00");
+    }
+
+    #[test]
+    fn decodes_hex_bytes() {
+        assert_eq!(decode_hex("00a0FF"), Some(vec![0x00, 0xA0, 0xFF]));
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("+1"), None);
+    }
+
+    #[test]
+    fn rejects_multi_byte_characters_in_hex() {
+        assert_eq!(decode_hex("é"), None);
+        assert_eq!(decode_hex("0é0"), None);
+    }
+}
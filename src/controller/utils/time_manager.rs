@@ -1,6 +1,8 @@
-pub const DISPLAY_REFRESH: u128 = 16;
-pub const OPCODE_REFRESH: u128 = 2000;
-pub const TIMER_TICK: u64 = 1000;
+pub const FRAME_RATE: u64 = 60;
+pub const MAX_CATCH_UP_FRAMES: u64 = 4;
+
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+const RATE_WINDOW: Duration = Duration::from_secs(1);
 
 use std::{
     sync::mpsc::Sender,
@@ -17,35 +19,122 @@ pub enum TimeTo {
     StopSound,
 }
 
+// Counts whole 60 Hz frames in the elapsed time. The rest of the time stays in
+// the accumulator (nanoseconds times the frame rate), so nothing is lost
+// between two calls and the average rate is exact. After a stall at most
+// MAX_CATCH_UP_FRAMES are returned, the rest is dropped.
+pub struct FrameClock {
+    last: Instant,
+    accumulator: u128,
+}
+
+impl Default for FrameClock {
+    fn default() -> Self {
+        FrameClock::new()
+    }
+}
+
+impl FrameClock {
+    pub fn new() -> FrameClock {
+        FrameClock {
+            last: Instant::now(),
+            accumulator: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.last = Instant::now();
+        self.accumulator = 0;
+    }
+
+    pub fn elapsed_frames(&mut self) -> u64 {
+        let now = Instant::now();
+        self.accumulator += now.duration_since(self.last).as_nanos() * FRAME_RATE as u128;
+        self.last = now;
+
+        let frames = (self.accumulator / NANOS_PER_SECOND) as u64;
+        self.accumulator %= NANOS_PER_SECOND;
+
+        frames.min(MAX_CATCH_UP_FRAMES)
+    }
+
+    pub fn time_to_next_frame(&self) -> Duration {
+        let remaining = (NANOS_PER_SECOND - self.accumulator).div_ceil(FRAME_RATE as u128);
+
+        Duration::from_nanos(remaining as u64)
+            .checked_sub(self.last.elapsed())
+            .unwrap_or_default()
+    }
+}
+
+// Measures the timer ticks and instructions that were really executed
+pub struct RateMeter {
+    start: Instant,
+    frames: u64,
+    instructions: u64,
+}
+
+impl Default for RateMeter {
+    fn default() -> Self {
+        RateMeter::new()
+    }
+}
+
+impl RateMeter {
+    pub fn new() -> RateMeter {
+        RateMeter {
+            start: Instant::now(),
+            frames: 0,
+            instructions: 0,
+        }
+    }
+
+    pub fn count_timer_tick(&mut self) {
+        self.frames += 1;
+    }
+
+    pub fn count_instructions(&mut self, instructions: u64) {
+        self.instructions += instructions;
+    }
+
+    // Timer rate in Hz and instructions per second, once per second
+    pub fn take_rates(&mut self) -> Option<(f64, f64)> {
+        let elapsed = self.start.elapsed();
+        if elapsed < RATE_WINDOW {
+            return None;
+        }
+
+        let seconds = elapsed.as_secs_f64();
+        let rates = (
+            self.frames as f64 / seconds,
+            self.instructions as f64 / seconds,
+        );
+        *self = RateMeter::new();
+
+        Some(rates)
+    }
+}
+
 pub struct TimeManager {
-    time: Instant,
+    clock: FrameClock,
     sender: Sender<TimeTo>,
 }
 
 impl TimeManager {
     pub fn new(new_sender: Sender<TimeTo>) -> TimeManager {
         TimeManager {
-            time: Instant::now(),
+            clock: FrameClock::new(),
             sender: new_sender,
         }
     }
 
     pub fn start_clock(&mut self) {
         loop {
-            if self.time.elapsed().as_millis() > DISPLAY_REFRESH {
+            if self.clock.elapsed_frames() > 0 {
                 self.sender.send(TimeTo::Update).unwrap_or(());
-                self.time = Instant::now();
-            }
-
-            if self.time.elapsed().as_millis() > 10000000 {
-                self.tick();
             }
 
-            thread::sleep(Duration::from_micros(TIMER_TICK));
+            thread::sleep(self.clock.time_to_next_frame());
         }
     }
-
-    fn tick(&mut self) {
-        self.time = Instant::now();
-    }
 }
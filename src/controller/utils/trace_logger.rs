@@ -0,0 +1,181 @@
+use crate::controller::Disassembler;
+use crate::defines::game_constants::DEFAULT_TRACE_SIZE;
+use crate::model::SymbolTable;
+
+use std::{
+    fs::{create_dir_all, rename, File},
+    io::{BufWriter, Write},
+    path::Path,
+    result,
+    sync::Arc,
+};
+
+pub const TRACE_PATH: &str = "TempFiles/Trace.log";
+
+pub struct TraceStep {
+    pub address: usize,
+    pub opcode: u16,
+    pub variable_register: Vec<u8>,
+    pub index_register: u16,
+    pub stack_pointer: usize,
+}
+
+pub struct TraceLogger {
+    path: String,
+    max_size: usize,
+    range: Option<(usize, usize)>,
+    writer: Option<BufWriter<File>>,
+    written: usize,
+    cycle: u64,
+    symbols: Arc<SymbolTable>,
+}
+
+impl Default for TraceLogger {
+    fn default() -> Self {
+        TraceLogger::new(TRACE_PATH.to_string(), DEFAULT_TRACE_SIZE)
+    }
+}
+
+impl TraceLogger {
+    pub fn new(new_path: String, new_max_size: usize) -> TraceLogger {
+        TraceLogger {
+            path: new_path,
+            max_size: new_max_size,
+            range: None,
+            writer: None,
+            written: 0,
+            cycle: 0,
+            symbols: Arc::new(SymbolTable::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) -> result::Result<(), String> {
+        if enabled == self.is_enabled() {
+            return Ok(());
+        }
+
+        if enabled {
+            self.open()?;
+            println!("Tracing to {}", self.path);
+        } else {
+            self.close();
+            println!("Tracing stopped");
+        }
+
+        Ok(())
+    }
+
+    pub fn toggle(&mut self) {
+        let enabled = !self.is_enabled();
+        if let Err(error) = self.set_enabled(enabled) {
+            println!("{}", error);
+        }
+    }
+
+    pub fn set_range(&mut self, range: Option<(usize, usize)>) {
+        self.range = range;
+    }
+
+    pub fn get_range(&self) -> Option<(usize, usize)> {
+        self.range
+    }
+
+    pub fn set_symbols(&mut self, symbols: Arc<SymbolTable>) {
+        self.symbols = symbols;
+    }
+
+    pub fn reset_cycles(&mut self) {
+        self.cycle = 0;
+    }
+
+    pub fn count_cycle(&mut self) {
+        self.cycle += 1;
+    }
+
+    pub fn wants(&self, address: usize) -> bool {
+        self.is_enabled()
+            && self
+                .range
+                .is_none_or(|(start, end)| (start..=end).contains(&address))
+    }
+
+    pub fn log(&mut self, step: &TraceStep) {
+        let registers: Vec<String> = step
+            .variable_register
+            .iter()
+            .map(|value| format!("{:02X}", value))
+            .collect();
+        let mut line = format!(
+            "{:>10} {:04X} {:04X} {:<20} V:{} I:{:04X} SP:{:X}",
+            self.cycle,
+            step.address,
+            step.opcode,
+            Disassembler::disassemble_with_symbols(&step.opcode, &self.symbols),
+            registers.join(" "),
+            step.index_register,
+            step.stack_pointer
+        );
+        if let Some(name) = self.symbols.name(step.address) {
+            line.push_str(&format!(" <{}>", name));
+        }
+        line.push('\n');
+
+        if self.written + line.len() > self.max_size {
+            if let Err(error) = self.rotate() {
+                println!("{}", error);
+                self.close();
+                return;
+            }
+        }
+
+        let result = match self.writer.as_mut() {
+            Some(writer) => writer.write_all(line.as_bytes()),
+            None => return,
+        };
+        match result {
+            Ok(()) => self.written += line.len(),
+            Err(error) => {
+                println!("Error: Could not write the trace: {}", error);
+                self.close();
+            }
+        }
+    }
+
+    fn open(&mut self) -> result::Result<(), String> {
+        if let Some(directory) = Path::new(&self.path).parent() {
+            create_dir_all(directory).map_err(|error| {
+                format!("Error: Could not create the trace directory: {}", error)
+            })?;
+        }
+        let file = File::create(&self.path)
+            .map_err(|error| format!("Error: Could not create {}: {}", self.path, error))?;
+        self.writer = Some(BufWriter::new(file));
+        self.written = 0;
+
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> result::Result<(), String> {
+        self.close();
+        rename(&self.path, format!("{}.1", self.path))
+            .map_err(|error| format!("Error: Could not rotate {}: {}", self.path, error))?;
+
+        self.open()
+    }
+
+    fn close(&mut self) {
+        if let Some(mut writer) = self.writer.take() {
+            writer.flush().unwrap_or(());
+        }
+    }
+}
+
+impl Drop for TraceLogger {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
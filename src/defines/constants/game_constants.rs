@@ -1 +1,6 @@
 pub const BASE_PROGRAM_SPEED: u64 = 10;
+pub const PALETTE_SIZE: usize = 4;
+pub const DEFAULT_PALETTE: [u32; PALETTE_SIZE] = [0x282828, 0xC8320C, 0x3C8CDC, 0xF0F0F0];
+pub const DEFAULT_REWIND_BUDGET: usize = 16 * 1024 * 1024;
+pub const STEP_HISTORY_SIZE: usize = 100_000;
+pub const DEFAULT_TRACE_SIZE: usize = 64 * 1024 * 1024;
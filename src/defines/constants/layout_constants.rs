@@ -1,4 +1,5 @@
 use crate::defines::constants::memory_constants;
+use crate::defines::Register;
 use sdl2::pixels::Color;
 
 pub const WINDOW_BACKGROUND: Color = Color::RGB(25, 35, 45);
@@ -13,7 +14,12 @@ pub const GAME_PIXEL_UNTEST: Color = Color::RGB(3, 0xC, 0x1F);
 pub const OPCODE_HIGHLIGHT_NORMAL: Color = Color::RGB(51, 51, 255);
 pub const OPCODE_HIGHLIGHT_DEBUG: Color = Color::RGB(204, 0, 0);
 pub const OPCODE_HIGHLIGHT_TEST: Color = Color::RGB(0, 0, 204);
+pub const OPCODE_CURSOR: Color = Color::RGB(230, 200, 40);
 pub const STACK_HIGHLIGHT: Color = Color::RGB(204, 0, 0);
+pub const REGISTER_EDIT: Color = Color::RGB(230, 200, 40);
+pub const HEAT_COLD: Color = Color::RGB(30, 60, 160);
+pub const HEAT_HOT: Color = Color::RGB(240, 60, 30);
+pub const HEAT_WIDTH: u32 = 12;
 
 //original 15
 //low scale 9
@@ -40,6 +46,16 @@ pub const MEMORY_HEIGHT: u32 =
 pub const MEMORY_WIDTH: u32 = OPCODE_WIDTH;
 pub const MEMORY_START_X: i32 = OPCODE_START_X;
 pub const MEMORY_START_Y: i32 = 2 * EDGE_SIZE + 3 * OUTLINE + GAME_HEIGHT as i32;
+pub const MEMORY_REGISTER_ROWS: [Option<Register>; 8] = [
+    Some(Register::ProgramCounter),
+    Some(Register::Index),
+    Some(Register::StackPointer),
+    None,
+    Some(Register::DelayTimer),
+    Some(Register::SoundTimer),
+    None,
+    None,
+];
 
 pub const INFO_HEIGHT: u32 = MEMORY_HEIGHT;
 pub const INFO_WIDTH: u32 = MEMORY_WIDTH;
@@ -69,3 +85,5 @@ pub const STACK_WIDTH: u32 = MEMORY_WIDTH / 5 * 3;
 /// 2 + 3 * EDGE_SIZE as u32;
 pub const STACK_START_X: i32 = MEMORY_START_X - STACK_WIDTH as i32 - EDGE_SIZE - 2 * OUTLINE;
 pub const STACK_START_Y: i32 = MEMORY_START_Y;
+pub const STACK_FRAME_LINES: usize = 2;
+pub const OPCODE_SCROLL_CONTEXT: usize = 8;
@@ -1,11 +1,11 @@
-pub const MEMORYSIZE: usize = 4096;
+pub const MEMORYSIZE: usize = 0x10000;
 pub const STACKSIZE: usize = 16;
 pub const VARIABLES_COUNT: usize = 16;
 pub const COLUMNS: usize = 64;
 pub const ROWS: usize = 32;
 pub const CARRY_FLAG: usize = 0xF;
 pub const KEY_COUNT: usize = 16;
-pub const MAX_PROGRAM_SIZE: usize = 3584;
+pub const MAX_PROGRAM_SIZE: usize = MEMORYSIZE - PROGRAM_START;
 pub const PROGRAM_START: usize = 0x200;
 pub const SCALE: usize = 15;
 pub const PROGRAM_STEP: usize = 2;
@@ -18,3 +18,8 @@ pub const FONTSET_LOW_SIZE: usize = 80;
 pub const FONTSET_HIGH_START: usize = FONTSET_LOW_SIZE;
 pub const FONTSET_HIGH_SIZE: usize = 160;
 pub const SCROLL_RANGE: usize = 4;
+pub const LONG_INSTRUCTION_SIZE: usize = 4;
+pub const DEFAULT_PLANES: u8 = 0x1;
+pub const PLANE_COUNT: u8 = 2;
+pub const AUDIO_PATTERN_SIZE: usize = 16;
+pub const BASE_PITCH: u8 = 64;
@@ -1,5 +1,6 @@
 pub mod font_constants;
 pub mod game_constants;
+#[cfg(feature = "gui")]
 pub mod layout_constants;
 pub mod memory_constants;
 
@@ -8,6 +8,9 @@ pub enum ProgramState {
     NewProgram,
     Quit,
     Idle,
+    SaveState(u8),
+    LoadState(u8),
+    Rewind,
 }
 
 #[derive(Copy, Clone, PartialEq)]
@@ -22,6 +25,7 @@ pub enum DebugState {
     Enabled,
     Disabled,
     Step,
+    StepBack,
     Breakpoint,
     Running,
     Stopped,
@@ -33,6 +37,81 @@ pub enum CpuState {
     Stopped,
 }
 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum WatchKind {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl WatchKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            WatchKind::Read => "R",
+            WatchKind::Write => "W",
+            WatchKind::ReadWrite => "RW",
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum MemoryOperation {
+    Read,
+    Write,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Register {
+    Variable(usize),
+    Index,
+    ProgramCounter,
+    StackPointer,
+    DelayTimer,
+    SoundTimer,
+    Stack(usize),
+}
+
+impl Register {
+    pub fn from_name(name: &str) -> Option<Register> {
+        let name = name.to_uppercase();
+        let register = match name.as_str() {
+            "I" => Register::Index,
+            "PC" => Register::ProgramCounter,
+            "SP" => Register::StackPointer,
+            "DT" => Register::DelayTimer,
+            "ST" => Register::SoundTimer,
+            _ if name.len() == 2 && name.starts_with('V') => {
+                Register::Variable(usize::from_str_radix(&name[1..], 16).ok()?)
+            }
+            _ if name.len() == 2 && name.starts_with('S') => {
+                Register::Stack(usize::from_str_radix(&name[1..], 16).ok()?)
+            }
+            _ => return None,
+        };
+
+        Some(register)
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Register::Variable(index) => format!("V{:X}", index),
+            Register::Index => "I".to_string(),
+            Register::ProgramCounter => "PC".to_string(),
+            Register::StackPointer => "SP".to_string(),
+            Register::DelayTimer => "DT".to_string(),
+            Register::SoundTimer => "ST".to_string(),
+            Register::Stack(index) => format!("S{:X}", index),
+        }
+    }
+
+    pub fn digits(self) -> usize {
+        match self {
+            Register::Index | Register::ProgramCounter | Register::Stack(_) => 4,
+            _ => 2,
+        }
+    }
+}
+
 #[derive(Copy, Clone, PartialEq)]
 pub enum KeyReset {
     Smooth,
@@ -73,3 +152,131 @@ impl BitState {
     pub const UNSET: u8 = 0;
     pub const SET: u8 = 1;
 }
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum QuirkProfile {
+    Vip,
+    Chip48,
+    SChip10,
+    SChip11,
+    XoChip,
+}
+
+impl QuirkProfile {
+    pub const ALL: [QuirkProfile; 5] = [
+        QuirkProfile::Vip,
+        QuirkProfile::Chip48,
+        QuirkProfile::SChip10,
+        QuirkProfile::SChip11,
+        QuirkProfile::XoChip,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            QuirkProfile::Vip => "VIP",
+            QuirkProfile::Chip48 => "CHIP-48",
+            QuirkProfile::SChip10 => "SCHIP 1.0",
+            QuirkProfile::SChip11 => "SCHIP 1.1",
+            QuirkProfile::XoChip => "XO-CHIP",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<QuirkProfile> {
+        match name.to_lowercase().replace(['-', '.', ' '], "").as_str() {
+            "vip" | "chip8" => Some(QuirkProfile::Vip),
+            "chip48" => Some(QuirkProfile::Chip48),
+            "schip10" => Some(QuirkProfile::SChip10),
+            "schip" | "schip11" => Some(QuirkProfile::SChip11),
+            "xochip" => Some(QuirkProfile::XoChip),
+            _ => None,
+        }
+    }
+
+    pub fn next(&self) -> QuirkProfile {
+        let index = QuirkProfile::ALL
+            .iter()
+            .position(|profile| profile == self)
+            .unwrap_or(0);
+
+        QuirkProfile::ALL[(index + 1) % QuirkProfile::ALL.len()]
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TimingMode {
+    Flat,
+    Vip,
+}
+
+impl TimingMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimingMode::Flat => "Flat",
+            TimingMode::Vip => "VIP",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<TimingMode> {
+        match name.to_lowercase().as_str() {
+            "flat" => Some(TimingMode::Flat),
+            "vip" => Some(TimingMode::Vip),
+            _ => None,
+        }
+    }
+
+    pub fn next(&self) -> TimingMode {
+        match self {
+            TimingMode::Flat => TimingMode::Vip,
+            TimingMode::Vip => TimingMode::Flat,
+        }
+    }
+}
+
+// Ordered so that every set contains the instructions of the previous ones
+#[derive(Copy, Clone, PartialEq, PartialOrd, Debug)]
+pub enum InstructionSet {
+    Chip8,
+    SChip,
+    XoChip,
+}
+
+impl InstructionSet {
+    pub fn name(&self) -> &'static str {
+        match self {
+            InstructionSet::Chip8 => "CHIP-8",
+            InstructionSet::SChip => "SCHIP",
+            InstructionSet::XoChip => "XO-CHIP",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<InstructionSet> {
+        match name.to_lowercase().replace(['-', '.', ' '], "").as_str() {
+            "chip8" => Some(InstructionSet::Chip8),
+            "schip" | "superchip" => Some(InstructionSet::SChip),
+            "xochip" => Some(InstructionSet::XoChip),
+            _ => None,
+        }
+    }
+
+    pub fn supports(&self, opcode: u16) -> bool {
+        let required = match opcode {
+            0x00C0..=0x00CF | 0x00FB..=0x00FF => InstructionSet::SChip,
+            0x00B0..=0x00BF | 0x00D0..=0x00DF | 0xF000 | 0xF002 => InstructionSet::XoChip,
+            _ if opcode & 0xF00F == 0x5002 || opcode & 0xF00F == 0x5003 => InstructionSet::XoChip,
+            _ => match opcode & 0xF0FF {
+                0xF030 | 0xF075 | 0xF085 => InstructionSet::SChip,
+                0xF001 | 0xF03A => InstructionSet::XoChip,
+                _ => InstructionSet::Chip8,
+            },
+        };
+
+        required <= *self
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum IndexIncrement {
+    Unchanged,
+    ByX,
+    ByXPlusOne,
+}
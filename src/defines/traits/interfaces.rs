@@ -1,6 +1,9 @@
+#[cfg(feature = "gui")]
 use crate::sdl2::{render::WindowCanvas, ttf::Sdl2TtfContext};
+#[cfg(feature = "gui")]
 use std::result::Result;
 
+#[cfg(feature = "gui")]
 pub trait IDisplay {
     fn update_info(&mut self);
     fn redraw(
@@ -1,3 +1,4 @@
+#[cfg(feature = "gui")]
 mod idisplay;
 mod interfaces;
 
@@ -1,9 +1,13 @@
 pub mod controller;
 pub mod defines;
 pub mod model;
+#[cfg(feature = "gui")]
 pub mod view;
 
+#[cfg(feature = "gui")]
 extern crate edit;
+#[cfg(feature = "gui")]
 extern crate native_dialog;
 extern crate rand;
+#[cfg(feature = "gui")]
 extern crate sdl2;
@@ -1,12 +1,15 @@
 extern crate chip8_rust;
-use chip8_rust::controller::Builder;
+use chip8_rust::controller::{Builder, LaunchOptions};
 use std::env;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() > 1 {
-        let mut builder = Builder::new();
-        let mut emulator = builder.build_emulator(args[1].clone());
-        emulator.start_program();
+    match LaunchOptions::from_args(&args[1..]) {
+        Ok(options) => {
+            let mut builder = Builder::new();
+            let mut emulator = builder.build_emulator(options);
+            emulator.start_program();
+        }
+        Err(error) => println!("{}", error),
     }
 }
@@ -1,7 +1,9 @@
-use crate::model::DebugProperties;
+use crate::model::{
+    Breakpoint, DebugProperties, DrawCallProfile, RegisterEdit, SymbolTable, WatchHit, Watchpoint,
+};
 
 use std::{
-    collections::HashMap,
+    collections::BTreeMap,
     sync::{Arc, Mutex},
 };
 
@@ -16,7 +18,56 @@ impl DebugPropertiesAccess {
         }
     }
 
-    pub fn get_breakpoints(&mut self) -> HashMap<usize, u16> {
+    pub fn get_breakpoints(&mut self) -> BTreeMap<usize, Breakpoint> {
         self.debug_properties.lock().unwrap().breakpoints.clone()
     }
+
+    pub fn get_watchpoints(&mut self) -> Vec<Watchpoint> {
+        self.debug_properties.lock().unwrap().watchpoints.clone()
+    }
+
+    pub fn get_last_watch_hit(&mut self) -> Option<WatchHit> {
+        self.debug_properties.lock().unwrap().last_watch_hit
+    }
+
+    pub fn get_cursor(&mut self) -> Option<usize> {
+        self.debug_properties.lock().unwrap().cursor
+    }
+
+    pub fn get_register_edit(&mut self) -> Option<RegisterEdit> {
+        self.debug_properties.lock().unwrap().register_edit.clone()
+    }
+
+    pub fn get_symbols(&mut self) -> Arc<SymbolTable> {
+        Arc::clone(&self.debug_properties.lock().unwrap().symbols)
+    }
+
+    pub fn get_heat(&mut self, addresses: &[usize]) -> Option<(Vec<u64>, u64)> {
+        let properties = self.debug_properties.lock().unwrap();
+        if !properties.profiling {
+            return None;
+        }
+
+        Some((
+            properties.profile.get_executions(addresses),
+            properties.profile.get_max_executions(),
+        ))
+    }
+
+    pub fn get_draw_calls(&mut self) -> Option<DrawCallProfile> {
+        let properties = self.debug_properties.lock().unwrap();
+        if !properties.profiling {
+            return None;
+        }
+
+        Some(properties.profile.get_draw_calls())
+    }
+
+    pub fn take_opcode_scroll(&mut self) -> Option<usize> {
+        self.debug_properties.lock().unwrap().opcode_scroll.take()
+    }
+
+    pub fn set_opcode_addresses(&mut self, addresses: Vec<usize>) {
+        self.debug_properties.lock().unwrap().opcode_addresses = addresses;
+    }
 }
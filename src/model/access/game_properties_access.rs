@@ -1,3 +1,4 @@
+use crate::defines::{game_constants::PALETTE_SIZE, QuirkProfile, TimingMode};
 use crate::model::GameProperties;
 
 use std::sync::{Arc, Mutex};
@@ -25,7 +26,28 @@ impl GamePropertiesAccess {
         self.game_properties.lock().unwrap().game_speed
     }
 
+    pub fn get_timing_mode(&mut self) -> TimingMode {
+        self.game_properties.lock().unwrap().timing_mode
+    }
+
+    pub fn get_rates(&mut self) -> (f64, f64) {
+        let properties = self.game_properties.lock().unwrap();
+        (properties.timer_rate, properties.instruction_rate)
+    }
+
     pub fn get_game_code(&mut self) -> Vec<u8> {
         self.game_properties.lock().unwrap().game_code.clone()
     }
+
+    pub fn get_quirk_profile(&mut self) -> QuirkProfile {
+        self.game_properties.lock().unwrap().quirk_profile
+    }
+
+    pub fn get_palette(&mut self) -> [u32; PALETTE_SIZE] {
+        self.game_properties.lock().unwrap().palette
+    }
+
+    pub fn get_control_page(&mut self) -> usize {
+        self.game_properties.lock().unwrap().control_page
+    }
 }
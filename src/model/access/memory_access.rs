@@ -1,9 +1,10 @@
 use crate::defines::memory_constants::{
-    COLUMNS, FLAG_REGISTER_SIZE, GRAPHIC_SIZE, MAX_PROGRAM_SIZE, MEMORYSIZE, PROGRAM_START,
-    PROGRAM_STEP, ROWS, STACKSIZE, VARIABLES_COUNT,
+    AUDIO_PATTERN_SIZE, COLUMNS, FLAG_REGISTER_SIZE, GRAPHIC_SIZE, MAX_PROGRAM_SIZE, MEMORYSIZE,
+    PROGRAM_START, PROGRAM_STEP, ROWS, STACKSIZE, VARIABLES_COUNT,
 };
 
-use crate::model::{Memory, Resolution};
+use crate::defines::Register;
+use crate::model::{CpuFault, Memory, MemoryEvent, Resolution};
 use std::sync::{Arc, Mutex};
 
 pub struct MemoryAccess {
@@ -16,7 +17,12 @@ impl MemoryAccess {
     }
 
     pub fn get_graphic_array(&mut self) -> Vec<u8> {
-        self.memory.lock().unwrap().graphic_array.clone()
+        self.memory
+            .lock()
+            .unwrap()
+            .graphic_array
+            .get_pixels()
+            .to_vec()
     }
 
     pub fn get_opcode(&mut self) -> u16 {
@@ -45,6 +51,22 @@ impl MemoryAccess {
         self.memory.lock().unwrap().stack_pointer
     }
 
+    pub fn get_call_frames(&mut self) -> Vec<(usize, usize)> {
+        let memory = self.memory.lock().unwrap();
+        memory.stack[..memory.stack_pointer.min(STACKSIZE)]
+            .iter()
+            .rev()
+            .map(|return_address| {
+                let return_address = *return_address as usize;
+                (return_address.saturating_sub(PROGRAM_STEP), return_address)
+            })
+            .collect()
+    }
+
+    pub fn get_fault(&mut self) -> Option<CpuFault> {
+        self.memory.lock().unwrap().fault
+    }
+
     pub fn get_variable_register(&mut self) -> Vec<u8> {
         let mut variable_register = vec![0; VARIABLES_COUNT];
         variable_register.copy_from_slice(&self.memory.lock().unwrap().variable_register);
@@ -75,6 +97,18 @@ impl MemoryAccess {
         self.memory.lock().unwrap().resolution
     }
 
+    pub fn get_selected_planes(&mut self) -> u8 {
+        self.memory.lock().unwrap().selected_planes
+    }
+
+    pub fn get_audio_pattern(&mut self) -> Option<[u8; AUDIO_PATTERN_SIZE]> {
+        self.memory.lock().unwrap().audio_pattern
+    }
+
+    pub fn get_pitch(&mut self) -> u8 {
+        self.memory.lock().unwrap().pitch
+    }
+
     pub fn get_code_snippet(&mut self, count: usize, offset: usize) -> Option<Vec<u16>> {
         if offset + count * 2 + 1 >= MAX_PROGRAM_SIZE {
             return None;
@@ -90,6 +124,147 @@ impl MemoryAccess {
         Some(code_lines)
     }
 
+    pub fn get_instruction_address(&mut self) -> usize {
+        self.memory.lock().unwrap().instruction_address
+    }
+
+    pub fn set_access_logging(&mut self, enabled: bool) {
+        let mut memory = self.memory.lock().unwrap();
+        memory.log_accesses = enabled;
+        if !enabled {
+            memory.access_log.clear();
+        }
+    }
+
+    pub fn take_access_log(&mut self) -> Vec<MemoryEvent> {
+        let mut memory = self.memory.lock().unwrap();
+        let mut access_log = Vec::new();
+        std::mem::swap(&mut access_log, &mut memory.access_log);
+
+        access_log
+    }
+
+    pub fn set_variable_register(&mut self, register: usize, value: u8) -> bool {
+        match self
+            .memory
+            .lock()
+            .unwrap()
+            .variable_register
+            .get_mut(register)
+        {
+            Some(entry) => {
+                *entry = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn set_index_register(&mut self, value: u16) -> bool {
+        self.memory.lock().unwrap().index_register = value;
+        true
+    }
+
+    pub fn set_program_counter(&mut self, value: usize) -> bool {
+        if value + 1 >= MEMORYSIZE {
+            return false;
+        }
+        self.memory.lock().unwrap().program_counter = value;
+        true
+    }
+
+    pub fn set_stack_pointer(&mut self, value: usize) -> bool {
+        if value > STACKSIZE {
+            return false;
+        }
+        self.memory.lock().unwrap().stack_pointer = value;
+        true
+    }
+
+    pub fn set_stack_entry(&mut self, index: usize, value: u16) -> bool {
+        match self.memory.lock().unwrap().stack.get_mut(index) {
+            Some(entry) => {
+                *entry = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn set_delay_timer(&mut self, value: u8) -> bool {
+        self.memory.lock().unwrap().delay_timer = value;
+        true
+    }
+
+    pub fn set_sound_timer(&mut self, value: u8) -> bool {
+        self.memory.lock().unwrap().sound_timer = value;
+        true
+    }
+
+    pub fn set_memory_at(&mut self, address: usize, value: u8) -> bool {
+        self.set_memory_range(address, &[value])
+    }
+
+    pub fn get_register(&mut self, register: Register) -> Option<usize> {
+        let memory = self.memory.lock().unwrap();
+        let value = match register {
+            Register::Variable(index) => *memory.variable_register.get(index)? as usize,
+            Register::Index => memory.index_register as usize,
+            Register::ProgramCounter => memory.program_counter,
+            Register::StackPointer => memory.stack_pointer,
+            Register::DelayTimer => memory.delay_timer as usize,
+            Register::SoundTimer => memory.sound_timer as usize,
+            Register::Stack(index) => *memory.stack.get(index)? as usize,
+        };
+
+        Some(value)
+    }
+
+    pub fn set_register(&mut self, register: Register, value: usize) -> bool {
+        if value >> (register.digits() * 4) != 0 {
+            return false;
+        }
+
+        match register {
+            Register::Variable(index) => self.set_variable_register(index, value as u8),
+            Register::Index => self.set_index_register(value as u16),
+            Register::ProgramCounter => self.set_program_counter(value),
+            Register::StackPointer => self.set_stack_pointer(value),
+            Register::DelayTimer => self.set_delay_timer(value as u8),
+            Register::SoundTimer => self.set_sound_timer(value as u8),
+            Register::Stack(index) => self.set_stack_entry(index, value as u16),
+        }
+    }
+
+    pub fn get_memory_range(&mut self, address: usize, length: usize) -> Option<Vec<u8>> {
+        self.memory
+            .lock()
+            .unwrap()
+            .memory
+            .get(address..address + length)
+            .map(|bytes| bytes.to_vec())
+    }
+
+    pub fn set_memory_range(&mut self, address: usize, bytes: &[u8]) -> bool {
+        match self
+            .memory
+            .lock()
+            .unwrap()
+            .memory
+            .get_mut(address..address + bytes.len())
+        {
+            Some(memory) => {
+                memory.copy_from_slice(bytes);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get_memory_at(&mut self, address: usize) -> Option<u8> {
+        self.memory.lock().unwrap().memory.get(address).cloned()
+    }
+
     pub fn get_opcode_at(&mut self, line: usize) -> Option<u16> {
         if line + 1 >= MAX_PROGRAM_SIZE {
             return None;
@@ -0,0 +1,92 @@
+use crate::model::{Condition, MemoryAccess, SymbolTable};
+
+use std::result;
+
+#[derive(Clone)]
+pub struct Breakpoint {
+    pub condition: Option<Condition>,
+    pub condition_text: String,
+    pub ignore_count: u32,
+    pub hit_count: u32,
+}
+
+impl Default for Breakpoint {
+    fn default() -> Self {
+        Breakpoint::new()
+    }
+}
+
+impl Breakpoint {
+    pub fn new() -> Breakpoint {
+        Breakpoint {
+            condition: None,
+            condition_text: String::new(),
+            ignore_count: 0,
+            hit_count: 0,
+        }
+    }
+
+    pub fn with_condition(
+        condition_text: Option<&str>,
+        ignore_count: u32,
+        symbols: &SymbolTable,
+    ) -> result::Result<Breakpoint, String> {
+        let mut breakpoint = Breakpoint::new();
+        if let Some(text) = condition_text {
+            breakpoint.condition = Some(Condition::parse(text, symbols)?);
+            breakpoint.condition_text = text.trim().to_string();
+        }
+        breakpoint.ignore_count = ignore_count;
+
+        Ok(breakpoint)
+    }
+
+    pub fn hit(&mut self, memory_access: &mut MemoryAccess) -> bool {
+        if let Some(condition) = &self.condition {
+            if !condition.evaluate(memory_access) {
+                return false;
+            }
+        }
+        self.hit_count += 1;
+
+        self.hit_count > self.ignore_count
+    }
+}
+
+// Text form "<address> [if <condition>] [ignore <count>]", the address can be a symbol
+#[derive(Clone, Debug)]
+pub struct BreakpointSpec {
+    pub address: usize,
+    pub condition: Option<String>,
+    pub ignore_count: u32,
+}
+
+impl BreakpointSpec {
+    pub fn parse(text: &str, symbols: &SymbolTable) -> result::Result<BreakpointSpec, String> {
+        let mut rest = text.trim();
+        let mut ignore_count = 0;
+        if let Some(position) = rest.rfind(" ignore ") {
+            let count = rest[position + " ignore ".len()..].trim();
+            ignore_count = count
+                .parse::<u32>()
+                .map_err(|_| format!("Error: Invalid ignore count: {}", count))?;
+            rest = rest[..position].trim();
+        }
+
+        let mut condition = None;
+        if let Some(position) = rest.find(" if ") {
+            condition = Some(rest[position + " if ".len()..].trim().to_string());
+            rest = rest[..position].trim();
+        }
+
+        let address = symbols
+            .resolve(rest)
+            .ok_or(format!("Error: Invalid breakpoint address: {}", rest))?;
+
+        Ok(BreakpointSpec {
+            address,
+            condition,
+            ignore_count,
+        })
+    }
+}
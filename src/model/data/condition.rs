@@ -0,0 +1,383 @@
+use crate::model::{MemoryAccess, SymbolTable};
+
+use std::result;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Operand {
+    Register(usize),
+    Index,
+    ProgramCounter,
+    StackPointer,
+    DelayTimer,
+    SoundTimer,
+    Memory(usize),
+    Value(usize),
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Condition {
+    Compare(Operand, Comparison, Operand),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+impl Condition {
+    pub fn parse(text: &str, symbols: &SymbolTable) -> result::Result<Condition, String> {
+        let tokens = Condition::tokenize(text)?;
+        let mut position = 0;
+        let condition = Condition::parse_or(&tokens, &mut position, symbols)?;
+        if position < tokens.len() {
+            return Err(format!(
+                "Error: Unexpected '{}' in condition: {}",
+                tokens[position], text
+            ));
+        }
+
+        Ok(condition)
+    }
+
+    pub fn evaluate(&self, memory_access: &mut MemoryAccess) -> bool {
+        match self {
+            Condition::Compare(left, comparison, right) => {
+                let left = Condition::read_operand(*left, memory_access);
+                let right = Condition::read_operand(*right, memory_access);
+                match comparison {
+                    Comparison::Equal => left == right,
+                    Comparison::NotEqual => left != right,
+                    Comparison::Less => left < right,
+                    Comparison::LessEqual => left <= right,
+                    Comparison::Greater => left > right,
+                    Comparison::GreaterEqual => left >= right,
+                }
+            }
+            Condition::And(left, right) => {
+                left.evaluate(memory_access) && right.evaluate(memory_access)
+            }
+            Condition::Or(left, right) => {
+                left.evaluate(memory_access) || right.evaluate(memory_access)
+            }
+        }
+    }
+
+    fn read_operand(operand: Operand, memory_access: &mut MemoryAccess) -> usize {
+        match operand {
+            Operand::Register(register) => memory_access.get_variable_register()[register] as usize,
+            Operand::Index => memory_access.get_index_register() as usize,
+            Operand::ProgramCounter => memory_access.get_program_counter(),
+            Operand::StackPointer => memory_access.get_stack_pointer(),
+            Operand::DelayTimer => memory_access.get_delay_timer() as usize,
+            Operand::SoundTimer => memory_access.get_sound_timer() as usize,
+            Operand::Memory(address) => memory_access.get_memory_at(address).unwrap_or(0) as usize,
+            Operand::Value(value) => value,
+        }
+    }
+
+    fn parse_or(
+        tokens: &[String],
+        position: &mut usize,
+        symbols: &SymbolTable,
+    ) -> result::Result<Condition, String> {
+        let mut condition = Condition::parse_and(tokens, position, symbols)?;
+        while Condition::accept(tokens, position, "||") {
+            let right = Condition::parse_and(tokens, position, symbols)?;
+            condition = Condition::Or(Box::new(condition), Box::new(right));
+        }
+
+        Ok(condition)
+    }
+
+    fn parse_and(
+        tokens: &[String],
+        position: &mut usize,
+        symbols: &SymbolTable,
+    ) -> result::Result<Condition, String> {
+        let mut condition = Condition::parse_comparison(tokens, position, symbols)?;
+        while Condition::accept(tokens, position, "&&") {
+            let right = Condition::parse_comparison(tokens, position, symbols)?;
+            condition = Condition::And(Box::new(condition), Box::new(right));
+        }
+
+        Ok(condition)
+    }
+
+    fn parse_comparison(
+        tokens: &[String],
+        position: &mut usize,
+        symbols: &SymbolTable,
+    ) -> result::Result<Condition, String> {
+        if Condition::accept(tokens, position, "(") {
+            let condition = Condition::parse_or(tokens, position, symbols)?;
+            if !Condition::accept(tokens, position, ")") {
+                return Err("Error: Missing ')' in condition".to_string());
+            }
+            return Ok(condition);
+        }
+
+        let left = Condition::parse_operand(tokens, position, symbols)?;
+        let comparison = match tokens.get(*position).map(|token| token.as_str()) {
+            Some("==") => Comparison::Equal,
+            Some("!=") => Comparison::NotEqual,
+            Some("<") => Comparison::Less,
+            Some("<=") => Comparison::LessEqual,
+            Some(">") => Comparison::Greater,
+            Some(">=") => Comparison::GreaterEqual,
+            Some(token) => return Err(format!("Error: Expected a comparison, got '{}'", token)),
+            None => return Err("Error: Expected a comparison at the end of the condition".into()),
+        };
+        *position += 1;
+        let right = Condition::parse_operand(tokens, position, symbols)?;
+
+        Ok(Condition::Compare(left, comparison, right))
+    }
+
+    // Numbers and memory addresses can also be given as symbol names
+    fn parse_operand(
+        tokens: &[String],
+        position: &mut usize,
+        symbols: &SymbolTable,
+    ) -> result::Result<Operand, String> {
+        let token = tokens
+            .get(*position)
+            .ok_or("Error: Expected a value at the end of the condition".to_string())?;
+        *position += 1;
+
+        if token == "[" {
+            let address = tokens
+                .get(*position)
+                .and_then(|token| symbols.resolve(token))
+                .ok_or("Error: Expected an address after '['".to_string())?;
+            *position += 1;
+            if !Condition::accept(tokens, position, "]") {
+                return Err("Error: Missing ']' in condition".to_string());
+            }
+            return Ok(Operand::Memory(address));
+        }
+
+        match token.to_uppercase().as_str() {
+            "I" => return Ok(Operand::Index),
+            "PC" => return Ok(Operand::ProgramCounter),
+            "SP" => return Ok(Operand::StackPointer),
+            "DT" => return Ok(Operand::DelayTimer),
+            "ST" => return Ok(Operand::SoundTimer),
+            _ => {}
+        }
+
+        if let Some(register) = parse_register(token) {
+            return Ok(Operand::Register(register));
+        }
+
+        symbols
+            .resolve(token)
+            .map(Operand::Value)
+            .ok_or(format!("Error: Unknown value in condition: {}", token))
+    }
+
+    fn accept(tokens: &[String], position: &mut usize, expected: &str) -> bool {
+        if tokens.get(*position).map(|token| token.as_str()) == Some(expected) {
+            *position += 1;
+            return true;
+        }
+
+        false
+    }
+
+    fn tokenize(text: &str) -> result::Result<Vec<String>, String> {
+        let mut tokens: Vec<String> = Vec::new();
+        let characters: Vec<char> = text.chars().collect();
+        let mut index = 0;
+
+        while index < characters.len() {
+            let character = characters[index];
+            let pair: String = characters[index..(index + 2).min(characters.len())]
+                .iter()
+                .collect();
+
+            if character.is_whitespace() {
+                index += 1;
+            } else if ["==", "!=", "<=", ">=", "&&", "||"].contains(&pair.as_str()) {
+                tokens.push(pair);
+                index += 2;
+            } else if "<>()[]".contains(character) {
+                tokens.push(character.to_string());
+                index += 1;
+            } else if Condition::is_word_start(character) {
+                let start = index;
+                index += 1;
+                while index < characters.len() && Condition::is_word_character(characters[index]) {
+                    index += 1;
+                }
+                tokens.push(characters[start..index].iter().collect());
+            } else {
+                return Err(format!(
+                    "Error: Unexpected character '{}' at column {} of condition",
+                    character,
+                    index + 1
+                ));
+            }
+        }
+
+        if tokens.is_empty() {
+            return Err("Error: The condition is empty".to_string());
+        }
+
+        Ok(tokens)
+    }
+
+    // Numbers with a "0x", '#' or '$' prefix and symbol names are single words
+    fn is_word_start(character: char) -> bool {
+        character.is_ascii_alphanumeric() || "_.#$".contains(character)
+    }
+
+    fn is_word_character(character: char) -> bool {
+        character.is_ascii_alphanumeric() || "_.-".contains(character)
+    }
+}
+
+pub fn parse_number(text: &str) -> Option<usize> {
+    if text.starts_with("0x") || text.starts_with("0X") {
+        usize::from_str_radix(&text[2..], 16).ok()
+    } else if text.starts_with('#') || text.starts_with('$') {
+        usize::from_str_radix(&text[1..], 16).ok()
+    } else {
+        text.parse::<usize>().ok()
+    }
+}
+
+pub fn parse_range(text: &str) -> Option<(usize, usize)> {
+    let mut bounds = text.splitn(2, '-');
+    let start = parse_number(bounds.next()?.trim())?;
+    let end = match bounds.next() {
+        Some(end) => parse_number(end.trim())?,
+        None => start,
+    };
+
+    if end < start {
+        return None;
+    }
+
+    Some((start, end))
+}
+
+pub fn parse_register(text: &str) -> Option<usize> {
+    if text.len() == 2 && (text.starts_with('V') || text.starts_with('v')) {
+        return usize::from_str_radix(&text[1..], 16).ok();
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Condition {
+        let mut symbols = SymbolTable::new();
+        symbols.insert("score", 0x3A0);
+        symbols.insert("player.x", 0x3A1);
+
+        Condition::parse(text, &symbols).unwrap()
+    }
+
+    fn compare(left: Operand, right: Operand) -> Condition {
+        Condition::Compare(left, Comparison::Equal, right)
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let first = compare(Operand::Register(0), Operand::Value(1));
+        let second = compare(Operand::Register(1), Operand::Value(2));
+        let third = compare(Operand::Register(2), Operand::Value(3));
+
+        assert_eq!(
+            parse("v0 == 1 || v1 == 2 && v2 == 3"),
+            Condition::Or(
+                Box::new(first.clone()),
+                Box::new(Condition::And(
+                    Box::new(second.clone()),
+                    Box::new(third.clone())
+                ))
+            )
+        );
+        assert_eq!(
+            parse("(v0 == 1 || v1 == 2) && v2 == 3"),
+            Condition::And(
+                Box::new(Condition::Or(Box::new(first), Box::new(second))),
+                Box::new(third)
+            )
+        );
+    }
+
+    #[test]
+    fn parses_registers_and_comparisons() {
+        assert_eq!(
+            parse("I >= 0x300"),
+            Condition::Compare(
+                Operand::Index,
+                Comparison::GreaterEqual,
+                Operand::Value(0x300)
+            )
+        );
+        assert_eq!(
+            parse("pc!=dt"),
+            Condition::Compare(
+                Operand::ProgramCounter,
+                Comparison::NotEqual,
+                Operand::DelayTimer
+            )
+        );
+    }
+
+    #[test]
+    fn accepts_every_number_prefix() {
+        for text in ["v0 == 0x10", "v0 == #10", "v0 == $10", "v0 == 16"].iter() {
+            assert_eq!(
+                parse(text),
+                compare(Operand::Register(0), Operand::Value(0x10))
+            );
+        }
+    }
+
+    #[test]
+    fn resolves_memory_addresses_and_symbols() {
+        assert_eq!(
+            parse("[0x300] == [$301]"),
+            compare(Operand::Memory(0x300), Operand::Memory(0x301))
+        );
+        assert_eq!(
+            parse("[score] == 0 && I == player.x"),
+            Condition::And(
+                Box::new(compare(Operand::Memory(0x3A0), Operand::Value(0))),
+                Box::new(compare(Operand::Index, Operand::Value(0x3A1)))
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_conditions() {
+        let symbols = SymbolTable::new();
+        for text in [
+            "",
+            "v0 ==",
+            "v0 1",
+            "(v0 == 1",
+            "[0x300 == 1",
+            "v0 == 1 v1",
+            "v0 == unknown",
+            "v0 == 1 @",
+        ]
+        .iter()
+        {
+            assert!(Condition::parse(text, &symbols).is_err(), "{}", text);
+        }
+    }
+}
@@ -1,7 +1,42 @@
-use std::collections::HashMap;
+use crate::defines::Register;
+use crate::model::{Breakpoint, Profile, SymbolTable, WatchHit, Watchpoint};
+
+use std::{collections::BTreeMap, sync::Arc};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RunTarget {
+    pub address: Option<usize>,
+    pub stack_pointer: Option<usize>,
+}
+
+impl RunTarget {
+    pub fn reached(&self, program_counter: usize, stack_pointer: usize) -> bool {
+        self.address
+            .is_none_or(|address| address == program_counter)
+            && self
+                .stack_pointer
+                .is_none_or(|depth| stack_pointer <= depth)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct RegisterEdit {
+    pub register: Register,
+    pub input: String,
+}
 
 pub struct DebugProperties {
-    pub breakpoints: HashMap<usize, u16>,
+    pub breakpoints: BTreeMap<usize, Breakpoint>,
+    pub watchpoints: Vec<Watchpoint>,
+    pub last_watch_hit: Option<WatchHit>,
+    pub run_target: Option<RunTarget>,
+    pub cursor: Option<usize>,
+    pub opcode_addresses: Vec<usize>,
+    pub opcode_scroll: Option<usize>,
+    pub register_edit: Option<RegisterEdit>,
+    pub profiling: bool,
+    pub profile: Profile,
+    pub symbols: Arc<SymbolTable>,
 }
 
 impl Default for DebugProperties {
@@ -13,7 +48,17 @@ impl Default for DebugProperties {
 impl DebugProperties {
     pub fn new() -> DebugProperties {
         DebugProperties {
-            breakpoints: HashMap::new(),
+            breakpoints: BTreeMap::new(),
+            watchpoints: Vec::new(),
+            last_watch_hit: None,
+            run_target: None,
+            cursor: None,
+            opcode_addresses: Vec::new(),
+            opcode_scroll: None,
+            register_edit: None,
+            profiling: false,
+            profile: Profile::new(),
+            symbols: Arc::new(SymbolTable::new()),
         }
     }
 
@@ -0,0 +1,149 @@
+use crate::defines::memory_constants::PLANE_COUNT;
+
+#[derive(Clone, PartialEq)]
+pub struct FrameBuffer {
+    pixels: Vec<u8>,
+    columns: usize,
+    rows: usize,
+}
+
+impl FrameBuffer {
+    pub fn new(columns: usize, rows: usize) -> FrameBuffer {
+        FrameBuffer {
+            pixels: vec![0; columns * rows],
+            columns,
+            rows,
+        }
+    }
+
+    pub fn from_pixels(columns: usize, rows: usize, pixels: Vec<u8>) -> Option<FrameBuffer> {
+        if pixels.len() != columns * rows {
+            return None;
+        }
+
+        Some(FrameBuffer {
+            pixels,
+            columns,
+            rows,
+        })
+    }
+
+    pub fn get_columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn get_rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn get_pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn get_pixel(&self, x: usize, y: usize) -> u8 {
+        self.pixels[y * self.columns + x]
+    }
+
+    pub fn set_pixel_at(&mut self, index: usize, value: u8) {
+        self.pixels[index] = value;
+    }
+
+    pub fn set_pixels(&mut self, pixels: &[u8]) {
+        self.pixels.copy_from_slice(pixels);
+    }
+
+    pub fn planes(selected_planes: u8) -> impl Iterator<Item = u8> {
+        (0..PLANE_COUNT)
+            .map(|plane| 0x1 << plane)
+            .filter(move |plane| selected_planes & plane != 0)
+    }
+
+    pub fn toggle_pixel(&mut self, x: usize, y: usize, plane: u8) -> bool {
+        let pixel = &mut self.pixels[y * self.columns + x];
+        let collision = *pixel & plane != 0;
+        *pixel ^= plane;
+
+        collision
+    }
+
+    pub fn clear(&mut self, selected_planes: u8) {
+        for pixel in self.pixels.iter_mut() {
+            *pixel &= !selected_planes;
+        }
+    }
+
+    pub fn scroll_up(&mut self, rows: usize, selected_planes: u8) {
+        let columns = self.columns;
+        let max_rows = self.rows;
+        self.shift(selected_planes, |x, y| {
+            if y + rows < max_rows {
+                Some((y + rows) * columns + x)
+            } else {
+                None
+            }
+        });
+    }
+
+    pub fn scroll_down(&mut self, rows: usize, selected_planes: u8) {
+        let columns = self.columns;
+        self.shift(selected_planes, |x, y| {
+            if y >= rows {
+                Some((y - rows) * columns + x)
+            } else {
+                None
+            }
+        });
+    }
+
+    pub fn scroll_left(&mut self, range: usize, selected_planes: u8) {
+        let columns = self.columns;
+        self.shift(selected_planes, |x, y| {
+            if x + range < columns {
+                Some(y * columns + x + range)
+            } else {
+                None
+            }
+        });
+    }
+
+    pub fn scroll_right(&mut self, range: usize, selected_planes: u8) {
+        let columns = self.columns;
+        self.shift(selected_planes, |x, y| {
+            if x >= range {
+                Some(y * columns + x - range)
+            } else {
+                None
+            }
+        });
+    }
+
+    pub fn resize(&mut self, columns: usize, rows: usize) {
+        let old_buffer = self.clone();
+        *self = FrameBuffer::new(columns, rows);
+
+        for y in 0..rows {
+            for x in 0..columns {
+                self.pixels[y * columns + x] = old_buffer
+                    .get_pixel(x * old_buffer.columns / columns, y * old_buffer.rows / rows);
+            }
+        }
+    }
+
+    fn shift<F>(&mut self, selected_planes: u8, source: F)
+    where
+        F: Fn(usize, usize) -> Option<usize>,
+    {
+        let old_pixels = self.pixels.clone();
+
+        for y in 0..self.rows {
+            for x in 0..self.columns {
+                let moved = match source(x, y) {
+                    Some(index) => old_pixels[index] & selected_planes,
+                    None => 0,
+                };
+                let pixel = &mut self.pixels[y * self.columns + x];
+                *pixel = (*pixel & !selected_planes) | moved;
+            }
+        }
+    }
+}
@@ -1,10 +1,19 @@
-use crate::defines::game_constants::BASE_PROGRAM_SPEED;
+use crate::defines::{
+    game_constants::{BASE_PROGRAM_SPEED, DEFAULT_PALETTE, PALETTE_SIZE},
+    QuirkProfile, TimingMode,
+};
 
 pub struct GameProperties {
     pub game_speed: u64,
+    pub timing_mode: TimingMode,
+    pub timer_rate: f64,
+    pub instruction_rate: f64,
     pub game_size: usize,
     pub game_name: String,
     pub game_code: Vec<u8>,
+    pub quirk_profile: QuirkProfile,
+    pub palette: [u32; PALETTE_SIZE],
+    pub control_page: usize,
 }
 
 impl Default for GameProperties {
@@ -17,9 +26,15 @@ impl GameProperties {
     pub fn new() -> GameProperties {
         GameProperties {
             game_speed: BASE_PROGRAM_SPEED,
+            timing_mode: TimingMode::Flat,
+            timer_rate: 0.0,
+            instruction_rate: 0.0,
             game_size: 0,
             game_name: String::new(),
             game_code: Vec::new(),
+            quirk_profile: QuirkProfile::SChip11,
+            palette: DEFAULT_PALETTE,
+            control_page: 0,
         }
     }
 
@@ -1,10 +1,7 @@
 use crate::defines::{memory_constants::KEY_COUNT, KeyPress, KeyReset};
-use crate::sdl2::keyboard::Keycode;
-use std::collections::HashMap;
 
 pub struct Keypad {
     keys: [u8; 16],
-    keymap: HashMap<Keycode, usize>,
     key_reset: KeyReset,
 }
 
@@ -16,30 +13,8 @@ impl Default for Keypad {
 
 impl Keypad {
     pub fn new() -> Keypad {
-        let new_keymap: HashMap<Keycode, usize> = vec![
-            (Keycode::Num1, 0x1),
-            (Keycode::Num2, 0x2),
-            (Keycode::Num3, 0x3),
-            (Keycode::Num4, 0xC),
-            (Keycode::Q, 0x4),
-            (Keycode::W, 0x5),
-            (Keycode::E, 0x6),
-            (Keycode::R, 0xD),
-            (Keycode::A, 0x7),
-            (Keycode::S, 0x8),
-            (Keycode::D, 0x9),
-            (Keycode::F, 0xE),
-            (Keycode::Y, 0xA),
-            (Keycode::X, 0x0),
-            (Keycode::C, 0xB),
-            (Keycode::V, 0xF),
-        ]
-        .into_iter()
-        .collect();
-
         Keypad {
             keys: [0; KEY_COUNT],
-            keymap: new_keymap,
             key_reset: KeyReset::Static,
         }
     }
@@ -53,9 +28,25 @@ impl Keypad {
         }
     }
 
-    pub fn press_key(&mut self, key: Keycode, value: KeyPress) {
-        if self.keymap.contains_key(&key) {
-            self.keys[self.keymap[&key]] = value as u8;
+    pub fn get_keys(&self) -> [u8; KEY_COUNT] {
+        self.keys
+    }
+
+    pub fn set_keys(&mut self, keys: [u8; KEY_COUNT]) {
+        self.keys = keys;
+    }
+
+    pub fn get_key_reset(&self) -> KeyReset {
+        self.key_reset
+    }
+
+    pub fn set_key_reset(&mut self, key_reset: KeyReset) {
+        self.key_reset = key_reset;
+    }
+
+    pub fn set_key(&mut self, key: u8, value: KeyPress) {
+        if key < KEY_COUNT as u8 {
+            self.keys[key as usize] = value as u8;
         }
     }
 
@@ -0,0 +1,480 @@
+use crate::defines::{
+    memory_constants::{
+        AUDIO_PATTERN_SIZE, COLUMNS, FLAG_REGISTER_SIZE, KEY_COUNT, MEMORYSIZE, ROWS, STACKSIZE,
+        VARIABLES_COUNT,
+    },
+    IndexIncrement, KeyReset, QuirkProfile, TimingMode,
+};
+use crate::model::{FrameBuffer, Keypad, Memory, Quirks, Resolution};
+
+use std::result;
+
+pub const SAVE_STATE_MAGIC: &[u8; 4] = b"C8ST";
+pub const SAVE_STATE_VERSION: u16 = 2;
+
+#[derive(Clone)]
+pub struct MachineState {
+    pub memory: Vec<u8>,
+    pub variable_register: [u8; VARIABLES_COUNT],
+    pub stack: [u16; STACKSIZE],
+    pub stack_pointer: usize,
+    pub program_counter: usize,
+    pub opcode: u16,
+    pub index_register: u16,
+    pub delay_timer: u8,
+    pub sound_timer: u8,
+    pub flag_register: [u8; FLAG_REGISTER_SIZE],
+    pub resolution: Resolution,
+    pub selected_planes: u8,
+    pub audio_pattern: Option<[u8; AUDIO_PATTERN_SIZE]>,
+    pub pitch: u8,
+    pub graphic_array: FrameBuffer,
+    pub random_seed: u64,
+    pub random_state: u64,
+    pub keys: [u8; KEY_COUNT],
+    pub key_reset: KeyReset,
+    pub quirks: Quirks,
+    pub speed: u64,
+    pub timing_mode: TimingMode,
+}
+
+impl MachineState {
+    pub fn capture(
+        memory: &Memory,
+        keypad: &Keypad,
+        quirks: &Quirks,
+        speed: u64,
+        timing_mode: TimingMode,
+    ) -> MachineState {
+        MachineState {
+            memory: memory.memory.to_vec(),
+            variable_register: memory.variable_register,
+            stack: memory.stack,
+            stack_pointer: memory.stack_pointer,
+            program_counter: memory.program_counter,
+            opcode: memory.opcode,
+            index_register: memory.index_register,
+            delay_timer: memory.delay_timer,
+            sound_timer: memory.sound_timer,
+            flag_register: memory.flag_register,
+            resolution: memory.resolution,
+            selected_planes: memory.selected_planes,
+            audio_pattern: memory.audio_pattern,
+            pitch: memory.pitch,
+            graphic_array: memory.graphic_array.clone(),
+            random_seed: memory.random_seed,
+            random_state: memory.random_source.get_state(),
+            keys: keypad.get_keys(),
+            key_reset: keypad.get_key_reset(),
+            quirks: *quirks,
+            speed,
+            timing_mode,
+        }
+    }
+
+    pub fn restore(&self, memory: &mut Memory, keypad: &mut Keypad, quirks: &mut Quirks) {
+        memory.memory.copy_from_slice(&self.memory);
+        memory.variable_register = self.variable_register;
+        memory.stack = self.stack;
+        memory.stack_pointer = self.stack_pointer;
+        memory.fault = None;
+        memory.program_counter = self.program_counter;
+        memory.opcode = self.opcode;
+        memory.index_register = self.index_register;
+        memory.delay_timer = self.delay_timer;
+        memory.sound_timer = self.sound_timer;
+        memory.flag_register = self.flag_register;
+        memory.resolution = self.resolution;
+        memory.selected_planes = self.selected_planes;
+        memory.audio_pattern = self.audio_pattern;
+        memory.pitch = self.pitch;
+        memory.graphic_array = self.graphic_array.clone();
+        memory.random_seed = self.random_seed;
+        memory.random_source.set_state(self.random_state);
+        keypad.set_keys(self.keys);
+        keypad.set_key_reset(self.key_reset);
+        *quirks = self.quirks;
+    }
+
+    pub fn rom_hash(code: &[u8]) -> u64 {
+        let mut hash: u64 = 0xCBF2_9CE4_8422_2325;
+        for byte in code.iter() {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01B3);
+        }
+
+        hash
+    }
+
+    pub fn to_bytes(&self, rom_hash: u64) -> Vec<u8> {
+        let mut writer = ByteWriter::new();
+        writer.write_bytes(SAVE_STATE_MAGIC);
+        writer.write_u16(SAVE_STATE_VERSION);
+        writer.write_u64(rom_hash);
+
+        writer.write_bytes(&self.memory);
+        writer.write_bytes(&self.variable_register);
+        for entry in self.stack.iter() {
+            writer.write_u16(*entry);
+        }
+        writer.write_u16(self.stack_pointer as u16);
+        writer.write_u16(self.program_counter as u16);
+        writer.write_u16(self.opcode);
+        writer.write_u16(self.index_register);
+        writer.write_u8(self.delay_timer);
+        writer.write_u8(self.sound_timer);
+        writer.write_bytes(&self.flag_register);
+        writer.write_u8(self.resolution as u8);
+        writer.write_u8(self.selected_planes);
+        match self.audio_pattern {
+            Some(pattern) => {
+                writer.write_u8(1);
+                writer.write_bytes(&pattern);
+            }
+            None => writer.write_u8(0),
+        }
+        writer.write_u8(self.pitch);
+        writer.write_u16(self.graphic_array.get_columns() as u16);
+        writer.write_u16(self.graphic_array.get_rows() as u16);
+        writer.write_bytes(self.graphic_array.get_pixels());
+        writer.write_u64(self.random_seed);
+        writer.write_u64(self.random_state);
+        writer.write_bytes(&self.keys);
+        writer.write_u8(MachineState::key_reset_to_byte(self.key_reset));
+        writer.write_quirks(&self.quirks);
+        writer.write_u64(self.speed);
+        writer.write_u8(self.timing_mode as u8);
+
+        writer.bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8], rom_hash: u64) -> result::Result<MachineState, String> {
+        let mut reader = ByteReader::new(bytes);
+        if reader.read_bytes(SAVE_STATE_MAGIC.len())? != &SAVE_STATE_MAGIC[..] {
+            return Err("Error: The file is not a save state".to_string());
+        }
+
+        let version = reader.read_u16()?;
+        if version != SAVE_STATE_VERSION {
+            return Err(format!(
+                "Error: Unsupported save state version {} (expected {})",
+                version, SAVE_STATE_VERSION
+            ));
+        }
+
+        let saved_hash = reader.read_u64()?;
+        if saved_hash != rom_hash {
+            return Err(format!(
+                "Error: The save state belongs to a different ROM (hash {:016X}, loaded ROM {:016X})",
+                saved_hash, rom_hash
+            ));
+        }
+
+        let memory = reader.read_bytes(MEMORYSIZE)?.to_vec();
+        let mut variable_register = [0; VARIABLES_COUNT];
+        variable_register.copy_from_slice(reader.read_bytes(VARIABLES_COUNT)?);
+        let mut stack = [0; STACKSIZE];
+        for entry in stack.iter_mut() {
+            *entry = reader.read_u16()?;
+        }
+        let stack_pointer = reader.read_u16()? as usize;
+        if stack_pointer > STACKSIZE {
+            return Err(format!(
+                "Error: Invalid stack pointer in save state: {}",
+                stack_pointer
+            ));
+        }
+        let program_counter = reader.read_u16()? as usize;
+        let opcode = reader.read_u16()?;
+        let index_register = reader.read_u16()?;
+        let delay_timer = reader.read_u8()?;
+        let sound_timer = reader.read_u8()?;
+        let mut flag_register = [0; FLAG_REGISTER_SIZE];
+        flag_register.copy_from_slice(reader.read_bytes(FLAG_REGISTER_SIZE)?);
+        let resolution = match reader.read_u8()? {
+            2 => Resolution::High,
+            _ => Resolution::Low,
+        };
+        let selected_planes = reader.read_u8()?;
+        let audio_pattern = match reader.read_u8()? {
+            0 => None,
+            _ => {
+                let mut pattern = [0; AUDIO_PATTERN_SIZE];
+                pattern.copy_from_slice(reader.read_bytes(AUDIO_PATTERN_SIZE)?);
+                Some(pattern)
+            }
+        };
+        let pitch = reader.read_u8()?;
+        let columns = reader.read_u16()? as usize;
+        let rows = reader.read_u16()? as usize;
+        if columns != COLUMNS * resolution as usize || rows != ROWS * resolution as usize {
+            return Err(format!(
+                "Error: Invalid framebuffer size in save state: {}x{}",
+                columns, rows
+            ));
+        }
+        let pixels = reader.read_bytes(columns * rows)?.to_vec();
+        let graphic_array = FrameBuffer::from_pixels(columns, rows, pixels)
+            .ok_or("Error: Invalid framebuffer in save state".to_string())?;
+        let random_seed = reader.read_u64()?;
+        let random_state = reader.read_u64()?;
+        let mut keys = [0; KEY_COUNT];
+        keys.copy_from_slice(reader.read_bytes(KEY_COUNT)?);
+        let key_reset = MachineState::byte_to_key_reset(reader.read_u8()?);
+        let quirks = reader.read_quirks()?;
+        let speed = reader.read_u64()?;
+        let timing_mode = match reader.read_u8()? {
+            1 => TimingMode::Vip,
+            _ => TimingMode::Flat,
+        };
+        if !reader.is_finished() {
+            return Err("Error: Unexpected data at the end of the save state".to_string());
+        }
+
+        Ok(MachineState {
+            memory,
+            variable_register,
+            stack,
+            stack_pointer,
+            program_counter,
+            opcode,
+            index_register,
+            delay_timer,
+            sound_timer,
+            flag_register,
+            resolution,
+            selected_planes,
+            audio_pattern,
+            pitch,
+            graphic_array,
+            random_seed,
+            random_state,
+            keys,
+            key_reset,
+            quirks,
+            speed,
+            timing_mode,
+        })
+    }
+
+    fn key_reset_to_byte(key_reset: KeyReset) -> u8 {
+        match key_reset {
+            KeyReset::Smooth => 0,
+            KeyReset::Static => 1,
+        }
+    }
+
+    fn byte_to_key_reset(value: u8) -> KeyReset {
+        match value {
+            0 => KeyReset::Smooth,
+            _ => KeyReset::Static,
+        }
+    }
+}
+
+struct ByteWriter {
+    bytes: Vec<u8>,
+}
+
+impl ByteWriter {
+    fn new() -> ByteWriter {
+        ByteWriter { bytes: Vec::new() }
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_bytes(&mut self, values: &[u8]) {
+        self.bytes.extend_from_slice(values);
+    }
+
+    fn write_quirks(&mut self, quirks: &Quirks) {
+        let profile = QuirkProfile::ALL
+            .iter()
+            .position(|profile| *profile == quirks.profile)
+            .unwrap_or(0);
+        let increment = match quirks.load_store_increment {
+            IndexIncrement::Unchanged => 0,
+            IndexIncrement::ByX => 1,
+            IndexIncrement::ByXPlusOne => 2,
+        };
+
+        self.write_u8(profile as u8);
+        self.write_u8(quirks.shift_uses_vy as u8);
+        self.write_u8(increment);
+        self.write_u8(quirks.jump_uses_vx as u8);
+        self.write_u8(quirks.clip_sprites as u8);
+        self.write_u8(quirks.vf_reset as u8);
+        self.write_u8(quirks.display_wait as u8);
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(bytes: &'a [u8]) -> ByteReader<'a> {
+        ByteReader { bytes, position: 0 }
+    }
+
+    fn read_bytes(&mut self, count: usize) -> result::Result<&'a [u8], String> {
+        if self.position + count > self.bytes.len() {
+            return Err("Error: The save state is truncated".to_string());
+        }
+        let bytes = &self.bytes[self.position..self.position + count];
+        self.position += count;
+
+        Ok(bytes)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.position == self.bytes.len()
+    }
+
+    fn read_u8(&mut self) -> result::Result<u8, String> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> result::Result<u16, String> {
+        let mut value = [0; 2];
+        value.copy_from_slice(self.read_bytes(2)?);
+
+        Ok(u16::from_le_bytes(value))
+    }
+
+    fn read_u64(&mut self) -> result::Result<u64, String> {
+        let mut value = [0; 8];
+        value.copy_from_slice(self.read_bytes(8)?);
+
+        Ok(u64::from_le_bytes(value))
+    }
+
+    fn read_quirks(&mut self) -> result::Result<Quirks, String> {
+        let profile = QuirkProfile::ALL
+            .get(self.read_u8()? as usize)
+            .cloned()
+            .unwrap_or(QuirkProfile::SChip11);
+        let shift_uses_vy = self.read_u8()? != 0;
+        let load_store_increment = match self.read_u8()? {
+            0 => IndexIncrement::Unchanged,
+            1 => IndexIncrement::ByX,
+            _ => IndexIncrement::ByXPlusOne,
+        };
+
+        Ok(Quirks {
+            profile,
+            shift_uses_vy,
+            load_store_increment,
+            jump_uses_vx: self.read_u8()? != 0,
+            clip_sprites: self.read_u8()? != 0,
+            vf_reset: self.read_u8()? != 0,
+            display_wait: self.read_u8()? != 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROM_HASH: u64 = 0x1234;
+
+    fn capture() -> MachineState {
+        let mut memory = Memory::new();
+        memory.program_counter = 0x2A4;
+        memory.variable_register[3] = 0x10;
+        memory.stack[0] = 0x202;
+        memory.stack_pointer = 1;
+        memory.graphic_array.toggle_pixel(5, 7, 1);
+
+        MachineState::capture(
+            &memory,
+            &Keypad::new(),
+            &Quirks::from_profile(QuirkProfile::Vip),
+            15,
+            TimingMode::Vip,
+        )
+    }
+
+    #[test]
+    fn round_trip_keeps_the_state() {
+        let state = capture();
+        let restored = MachineState::from_bytes(&state.to_bytes(ROM_HASH), ROM_HASH).unwrap();
+
+        assert_eq!(restored.to_bytes(ROM_HASH), state.to_bytes(ROM_HASH));
+        assert_eq!(restored.program_counter, 0x2A4);
+        assert_eq!(restored.variable_register[3], 0x10);
+        assert_eq!(restored.stack_pointer, 1);
+        assert!(restored.graphic_array == state.graphic_array);
+        assert_eq!(restored.graphic_array.get_pixel(5, 7), 1);
+        assert!(restored.quirks == state.quirks);
+        assert_eq!(restored.speed, 15);
+        assert!(restored.timing_mode == TimingMode::Vip);
+    }
+
+    #[test]
+    fn rejects_save_states_of_an_older_version() {
+        let mut bytes = capture().to_bytes(ROM_HASH);
+        let version = SAVE_STATE_MAGIC.len();
+        bytes[version..version + 2].copy_from_slice(&1u16.to_le_bytes());
+
+        let error = MachineState::from_bytes(&bytes, ROM_HASH).err().unwrap();
+        assert!(
+            error.contains("Unsupported save state version 1"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn rejects_another_rom() {
+        let bytes = capture().to_bytes(ROM_HASH);
+
+        assert!(MachineState::from_bytes(&bytes, ROM_HASH + 1).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_and_trailing_bytes() {
+        let mut bytes = capture().to_bytes(ROM_HASH);
+        bytes.push(0);
+        assert!(MachineState::from_bytes(&bytes, ROM_HASH).is_err());
+
+        bytes.truncate(bytes.len() - 2);
+        assert!(MachineState::from_bytes(&bytes, ROM_HASH).is_err());
+    }
+
+    #[test]
+    fn rejects_an_empty_framebuffer() {
+        let mut state = capture();
+        state.graphic_array = FrameBuffer::new(0, 0);
+
+        assert!(MachineState::from_bytes(&state.to_bytes(ROM_HASH), ROM_HASH).is_err());
+    }
+
+    #[test]
+    fn rejects_a_framebuffer_that_does_not_match_the_resolution() {
+        let mut state = capture();
+        state.resolution = Resolution::High;
+
+        assert!(MachineState::from_bytes(&state.to_bytes(ROM_HASH), ROM_HASH).is_err());
+    }
+
+    #[test]
+    fn rejects_a_stack_pointer_past_the_stack() {
+        let mut state = capture();
+        state.stack_pointer = STACKSIZE + 1;
+
+        assert!(MachineState::from_bytes(&state.to_bytes(ROM_HASH), ROM_HASH).is_err());
+    }
+}
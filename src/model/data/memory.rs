@@ -1,6 +1,12 @@
-use crate::defines::memory_constants::{
-    FLAG_REGISTER_SIZE, GRAPHIC_SIZE, MEMORYSIZE, PROGRAM_START, STACKSIZE, VARIABLES_COUNT,
+use crate::defines::{
+    memory_constants::{
+        AUDIO_PATTERN_SIZE, BASE_PITCH, COLUMNS, DEFAULT_PLANES, FLAG_REGISTER_SIZE, MEMORYSIZE,
+        PROGRAM_START, ROWS, STACKSIZE, VARIABLES_COUNT,
+    },
+    MemoryOperation,
 };
+use crate::model::{CpuFault, FrameBuffer, MemoryEvent, RandomSource, XorShiftRandom};
+use std::mem;
 
 #[derive(Copy, Clone, PartialEq)]
 pub enum Resolution {
@@ -12,7 +18,7 @@ pub struct Memory {
     pub memory: [u8; MEMORYSIZE],
     pub delay_timer: u8,
     pub sound_timer: u8,
-    pub graphic_array: Vec<u8>,
+    pub graphic_array: FrameBuffer,
     pub variable_register: [u8; VARIABLES_COUNT],
     pub stack_pointer: usize,
     pub program_counter: usize,
@@ -21,6 +27,15 @@ pub struct Memory {
     pub index_register: u16,
     pub flag_register: [u8; FLAG_REGISTER_SIZE],
     pub resolution: Resolution,
+    pub selected_planes: u8,
+    pub audio_pattern: Option<[u8; AUDIO_PATTERN_SIZE]>,
+    pub pitch: u8,
+    pub random_seed: u64,
+    pub random_source: Box<dyn RandomSource>,
+    pub instruction_address: usize,
+    pub log_accesses: bool,
+    pub access_log: Vec<MemoryEvent>,
+    pub fault: Option<CpuFault>,
 }
 
 impl Default for Memory {
@@ -31,11 +46,15 @@ impl Default for Memory {
 
 impl Memory {
     pub fn new() -> Memory {
+        Memory::with_seed(rand::random())
+    }
+
+    pub fn with_seed(seed: u64) -> Memory {
         Memory {
             memory: [0; MEMORYSIZE],
             delay_timer: 0,
             sound_timer: 0,
-            graphic_array: vec![0; GRAPHIC_SIZE],
+            graphic_array: FrameBuffer::new(COLUMNS, ROWS),
 
             variable_register: [0; STACKSIZE],
             stack_pointer: 0,
@@ -45,10 +64,59 @@ impl Memory {
             index_register: 0,
             flag_register: [0; FLAG_REGISTER_SIZE],
             resolution: Resolution::Low,
+            selected_planes: DEFAULT_PLANES,
+            audio_pattern: None,
+            pitch: BASE_PITCH,
+            random_seed: seed,
+            random_source: Box::new(XorShiftRandom::new(seed)),
+            instruction_address: PROGRAM_START,
+            log_accesses: false,
+            access_log: Vec::new(),
+            fault: None,
         }
     }
 
     pub fn reset(&mut self) {
-        *self = Memory::new();
+        let seed = self.random_seed;
+        let mut random_source =
+            mem::replace(&mut self.random_source, Box::new(XorShiftRandom::new(seed)));
+        random_source.seed(seed);
+
+        let log_accesses = self.log_accesses;
+        *self = Memory::with_seed(seed);
+        self.random_source = random_source;
+        self.log_accesses = log_accesses;
+    }
+
+    pub fn read_byte(&mut self, address: usize) -> u8 {
+        let value = self.memory[address];
+        if self.log_accesses {
+            self.access_log.push(MemoryEvent {
+                address,
+                operation: MemoryOperation::Read,
+                old_value: value,
+                new_value: value,
+            });
+        }
+
+        value
+    }
+
+    pub fn write_byte(&mut self, address: usize, value: u8) {
+        let old_value = self.memory[address];
+        self.memory[address] = value;
+        if self.log_accesses {
+            self.access_log.push(MemoryEvent {
+                address,
+                operation: MemoryOperation::Write,
+                old_value,
+                new_value: value,
+            });
+        }
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.random_seed = seed;
+        self.random_source.seed(seed);
     }
 }
@@ -1,11 +1,35 @@
+mod breakpoint;
+mod condition;
 mod debug_properties;
+mod frame_buffer;
 mod game_properties;
 mod keypad;
+mod machine_state;
 mod memory;
+mod profile;
+mod quirks;
+mod random_source;
+mod rewind_buffer;
 mod states;
+mod step_history;
+mod symbol_table;
+mod watchpoint;
 
-pub use self::debug_properties::DebugProperties;
+pub use self::breakpoint::{Breakpoint, BreakpointSpec};
+pub use self::condition::{
+    parse_number, parse_range, parse_register, Comparison, Condition, Operand,
+};
+pub use self::debug_properties::{DebugProperties, RegisterEdit, RunTarget};
+pub use self::frame_buffer::FrameBuffer;
 pub use self::game_properties::GameProperties;
 pub use self::keypad::Keypad;
+pub use self::machine_state::{MachineState, SAVE_STATE_VERSION};
 pub use self::memory::{Memory, Resolution};
+pub use self::profile::{DrawCallProfile, Profile, SubroutineProfile};
+pub use self::quirks::Quirks;
+pub use self::random_source::{RandomSource, XorShiftRandom};
+pub use self::rewind_buffer::RewindBuffer;
 pub use self::states::States;
+pub use self::step_history::StepHistory;
+pub use self::symbol_table::SymbolTable;
+pub use self::watchpoint::{MemoryEvent, WatchHit, Watchpoint};
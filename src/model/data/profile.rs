@@ -0,0 +1,144 @@
+use crate::defines::memory_constants::{MEMORYSIZE, STACKSIZE};
+
+use std::collections::HashMap;
+
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct SubroutineProfile {
+    pub calls: u64,
+    pub instructions: u64,
+    pub self_instructions: u64,
+}
+
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct DrawCallProfile {
+    pub last_frame: u64,
+    pub max_frame: u64,
+    pub total: u64,
+    pub frames: u64,
+}
+
+pub struct Profile {
+    executions: Vec<u64>,
+    max_executions: u64,
+    total_executions: u64,
+    subroutines: HashMap<usize, SubroutineProfile>,
+    call_stack: Vec<usize>,
+    frame_draw_calls: u64,
+    draw_calls: DrawCallProfile,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Profile::new()
+    }
+}
+
+impl Profile {
+    pub fn new() -> Profile {
+        Profile {
+            executions: vec![0; MEMORYSIZE],
+            max_executions: 0,
+            total_executions: 0,
+            subroutines: HashMap::new(),
+            call_stack: Vec::new(),
+            frame_draw_calls: 0,
+            draw_calls: DrawCallProfile::default(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Profile::new();
+    }
+
+    pub fn record(&mut self, address: usize, opcode: u16) {
+        if let Some(count) = self.executions.get_mut(address) {
+            *count += 1;
+            self.max_executions = self.max_executions.max(*count);
+        }
+        self.total_executions += 1;
+
+        if let Some(current) = self.call_stack.last() {
+            self.subroutines
+                .entry(*current)
+                .or_default()
+                .self_instructions += 1;
+        }
+        // Recursive subroutines are on the stack more than once but run each instruction once
+        for (index, subroutine) in self.call_stack.iter().enumerate() {
+            if self.call_stack[..index].contains(subroutine) {
+                continue;
+            }
+            self.subroutines
+                .entry(*subroutine)
+                .or_default()
+                .instructions += 1;
+        }
+
+        match opcode & 0xF000 {
+            0x2000 => {
+                let target = (opcode & 0x0FFF) as usize;
+                self.subroutines.entry(target).or_default().calls += 1;
+                if self.call_stack.len() >= STACKSIZE {
+                    self.call_stack.remove(0);
+                }
+                self.call_stack.push(target);
+            }
+            0xD000 => self.frame_draw_calls += 1,
+            _ if opcode == 0x00EE => {
+                self.call_stack.pop();
+            }
+            _ => {}
+        }
+    }
+
+    pub fn end_frame(&mut self) {
+        self.draw_calls.last_frame = self.frame_draw_calls;
+        self.draw_calls.max_frame = self.draw_calls.max_frame.max(self.frame_draw_calls);
+        self.draw_calls.total += self.frame_draw_calls;
+        self.draw_calls.frames += 1;
+        self.frame_draw_calls = 0;
+    }
+
+    pub fn get_executions(&self, addresses: &[usize]) -> Vec<u64> {
+        addresses
+            .iter()
+            .map(|address| self.executions.get(*address).cloned().unwrap_or(0))
+            .collect()
+    }
+
+    pub fn get_max_executions(&self) -> u64 {
+        self.max_executions
+    }
+
+    pub fn get_total_executions(&self) -> u64 {
+        self.total_executions
+    }
+
+    pub fn get_draw_calls(&self) -> DrawCallProfile {
+        self.draw_calls
+    }
+
+    pub fn hottest_addresses(&self) -> Vec<(usize, u64)> {
+        let mut addresses: Vec<(usize, u64)> = self
+            .executions
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(address, count)| (address, *count))
+            .collect();
+        addresses.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        addresses
+    }
+
+    pub fn hottest_subroutines(&self) -> Vec<(usize, SubroutineProfile)> {
+        let mut subroutines: Vec<(usize, SubroutineProfile)> = self
+            .subroutines
+            .iter()
+            .map(|(address, profile)| (*address, *profile))
+            .collect();
+        subroutines.sort_by(|a, b| b.1.instructions.cmp(&a.1.instructions).then(a.0.cmp(&b.0)));
+
+        subroutines
+    }
+}
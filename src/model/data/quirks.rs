@@ -0,0 +1,74 @@
+use crate::defines::{IndexIncrement, QuirkProfile};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Quirks {
+    pub profile: QuirkProfile,
+    pub shift_uses_vy: bool,
+    pub load_store_increment: IndexIncrement,
+    pub jump_uses_vx: bool,
+    pub clip_sprites: bool,
+    pub vf_reset: bool,
+    pub display_wait: bool,
+}
+
+impl Default for Quirks {
+    fn default() -> Self {
+        Quirks::from_profile(QuirkProfile::SChip11)
+    }
+}
+
+impl Quirks {
+    pub fn from_profile(profile: QuirkProfile) -> Quirks {
+        match profile {
+            QuirkProfile::Vip => Quirks {
+                profile,
+                shift_uses_vy: true,
+                load_store_increment: IndexIncrement::ByXPlusOne,
+                jump_uses_vx: false,
+                clip_sprites: true,
+                vf_reset: true,
+                display_wait: true,
+            },
+            QuirkProfile::Chip48 => Quirks {
+                profile,
+                shift_uses_vy: false,
+                load_store_increment: IndexIncrement::ByX,
+                jump_uses_vx: true,
+                clip_sprites: true,
+                vf_reset: false,
+                display_wait: false,
+            },
+            QuirkProfile::SChip10 => Quirks {
+                profile,
+                shift_uses_vy: false,
+                load_store_increment: IndexIncrement::ByX,
+                jump_uses_vx: true,
+                clip_sprites: true,
+                vf_reset: false,
+                display_wait: false,
+            },
+            QuirkProfile::SChip11 => Quirks {
+                profile,
+                shift_uses_vy: false,
+                load_store_increment: IndexIncrement::Unchanged,
+                jump_uses_vx: true,
+                clip_sprites: true,
+                vf_reset: false,
+                display_wait: false,
+            },
+            QuirkProfile::XoChip => Quirks {
+                profile,
+                shift_uses_vy: true,
+                load_store_increment: IndexIncrement::ByXPlusOne,
+                jump_uses_vx: false,
+                clip_sprites: false,
+                vf_reset: false,
+                display_wait: false,
+            },
+        }
+    }
+
+    pub fn set_profile(&mut self, profile: QuirkProfile) {
+        *self = Quirks::from_profile(profile);
+    }
+}
@@ -0,0 +1,46 @@
+pub trait RandomSource: Send {
+    fn next_byte(&mut self) -> u8;
+    fn seed(&mut self, seed: u64);
+    fn get_state(&self) -> u64;
+    fn set_state(&mut self, state: u64);
+}
+
+pub struct XorShiftRandom {
+    state: u64,
+}
+
+impl XorShiftRandom {
+    pub fn new(seed: u64) -> XorShiftRandom {
+        let mut random = XorShiftRandom { state: 0 };
+        random.seed(seed);
+
+        random
+    }
+}
+
+impl RandomSource for XorShiftRandom {
+    fn next_byte(&mut self) -> u8 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+
+        (self.state.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 56) as u8
+    }
+
+    fn seed(&mut self, seed: u64) {
+        let mut value = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^= value >> 31;
+
+        self.set_state(value);
+    }
+
+    fn get_state(&self) -> u64 {
+        self.state
+    }
+
+    fn set_state(&mut self, state: u64) {
+        self.state = if state == 0 { 0x1 } else { state };
+    }
+}
@@ -0,0 +1,126 @@
+use crate::model::MachineState;
+
+use std::collections::VecDeque;
+
+const MAX_RUN: usize = 0xFFFF;
+
+struct RewindFrame {
+    length: usize,
+    delta: Vec<u8>,
+}
+
+pub struct RewindBuffer {
+    frames: VecDeque<RewindFrame>,
+    current: Vec<u8>,
+    used_bytes: usize,
+    budget: usize,
+}
+
+impl RewindBuffer {
+    pub fn new(budget: usize) -> RewindBuffer {
+        RewindBuffer {
+            frames: VecDeque::new(),
+            current: Vec::new(),
+            used_bytes: 0,
+            budget,
+        }
+    }
+
+    pub fn push(&mut self, state: &MachineState) {
+        let bytes = state.to_bytes(0);
+        if !self.current.is_empty() {
+            let delta = RewindBuffer::compress(&RewindBuffer::xor(&self.current, &bytes));
+            self.used_bytes += delta.len();
+            self.frames.push_back(RewindFrame {
+                length: self.current.len(),
+                delta,
+            });
+        }
+        self.current = bytes;
+
+        while self.used_bytes + self.current.len() > self.budget {
+            match self.frames.pop_front() {
+                Some(frame) => self.used_bytes -= frame.delta.len(),
+                None => break,
+            }
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<MachineState> {
+        let frame = self.frames.pop_back()?;
+        self.used_bytes -= frame.delta.len();
+
+        let mut previous =
+            RewindBuffer::xor(&self.current, &RewindBuffer::decompress(&frame.delta));
+        previous.truncate(frame.length);
+        self.current = previous;
+
+        MachineState::from_bytes(&self.current, 0).ok()
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.current.clear();
+        self.used_bytes = 0;
+    }
+
+    pub fn get_frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn get_used_bytes(&self) -> usize {
+        self.used_bytes + self.current.len()
+    }
+
+    pub fn get_budget(&self) -> usize {
+        self.budget
+    }
+
+    fn xor(first: &[u8], second: &[u8]) -> Vec<u8> {
+        let length = first.len().max(second.len());
+        (0..length)
+            .map(|index| first.get(index).unwrap_or(&0) ^ second.get(index).unwrap_or(&0))
+            .collect()
+    }
+
+    // Runs of unchanged bytes are stored as a count, followed by the count and values of changed bytes
+    fn compress(delta: &[u8]) -> Vec<u8> {
+        let mut compressed = Vec::new();
+        let mut index = 0;
+
+        while index < delta.len() {
+            let zero_start = index;
+            while index < delta.len() && delta[index] == 0 && index - zero_start < MAX_RUN {
+                index += 1;
+            }
+            let literal_start = index;
+            while index < delta.len() && delta[index] != 0 && index - literal_start < MAX_RUN {
+                index += 1;
+            }
+
+            compressed.extend_from_slice(&((literal_start - zero_start) as u16).to_le_bytes());
+            compressed.extend_from_slice(&((index - literal_start) as u16).to_le_bytes());
+            compressed.extend_from_slice(&delta[literal_start..index]);
+        }
+
+        compressed
+    }
+
+    fn decompress(compressed: &[u8]) -> Vec<u8> {
+        let mut delta = Vec::new();
+        let mut index = 0;
+
+        while index + 4 <= compressed.len() {
+            let zeros = u16::from_le_bytes([compressed[index], compressed[index + 1]]) as usize;
+            let literals =
+                u16::from_le_bytes([compressed[index + 2], compressed[index + 3]]) as usize;
+            index += 4;
+
+            delta.resize(delta.len() + zeros, 0);
+            delta.extend_from_slice(&compressed[index..index + literals]);
+            index += literals;
+        }
+
+        delta
+    }
+}
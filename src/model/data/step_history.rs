@@ -0,0 +1,161 @@
+use crate::defines::memory_constants::{
+    AUDIO_PATTERN_SIZE, FLAG_REGISTER_SIZE, KEY_COUNT, MEMORYSIZE, STACKSIZE, VARIABLES_COUNT,
+};
+use crate::model::{FrameBuffer, Keypad, Memory, Resolution};
+
+use std::collections::VecDeque;
+
+enum DisplayChange {
+    Unchanged,
+    Pixels(Vec<(usize, u8)>),
+    Complete(FrameBuffer),
+}
+
+struct StepRecord {
+    variable_register: [u8; VARIABLES_COUNT],
+    stack: [u16; STACKSIZE],
+    stack_pointer: usize,
+    program_counter: usize,
+    opcode: u16,
+    index_register: u16,
+    delay_timer: u8,
+    sound_timer: u8,
+    flag_register: [u8; FLAG_REGISTER_SIZE],
+    resolution: Resolution,
+    selected_planes: u8,
+    audio_pattern: Option<[u8; AUDIO_PATTERN_SIZE]>,
+    pitch: u8,
+    random_state: u64,
+    keys: [u8; KEY_COUNT],
+    // Every store opcode writes at most one byte per register starting at I
+    stored_address: usize,
+    stored_bytes: Vec<u8>,
+    display: DisplayChange,
+}
+
+pub struct StepHistory {
+    records: VecDeque<StepRecord>,
+    pending: Option<(StepRecord, FrameBuffer)>,
+    capacity: usize,
+}
+
+impl StepHistory {
+    pub fn new(capacity: usize) -> StepHistory {
+        StepHistory {
+            records: VecDeque::new(),
+            pending: None,
+            capacity,
+        }
+    }
+
+    pub fn begin_step(&mut self, memory: &Memory, keypad: &Keypad) {
+        let stored_address = memory.index_register as usize;
+        let stored_end = (stored_address + VARIABLES_COUNT).min(MEMORYSIZE);
+        let record = StepRecord {
+            variable_register: memory.variable_register,
+            stack: memory.stack,
+            stack_pointer: memory.stack_pointer,
+            program_counter: memory.program_counter,
+            opcode: memory.opcode,
+            index_register: memory.index_register,
+            delay_timer: memory.delay_timer,
+            sound_timer: memory.sound_timer,
+            flag_register: memory.flag_register,
+            resolution: memory.resolution,
+            selected_planes: memory.selected_planes,
+            audio_pattern: memory.audio_pattern,
+            pitch: memory.pitch,
+            random_state: memory.random_source.get_state(),
+            keys: keypad.get_keys(),
+            stored_address,
+            stored_bytes: memory.memory[stored_address.min(stored_end)..stored_end].to_vec(),
+            display: DisplayChange::Unchanged,
+        };
+
+        self.pending = Some((record, memory.graphic_array.clone()));
+    }
+
+    pub fn finish_step(&mut self, memory: &Memory) {
+        if let Some((mut record, old_display)) = self.pending.take() {
+            record.display = StepHistory::compare_display(&old_display, &memory.graphic_array);
+            if self.records.len() >= self.capacity {
+                self.records.pop_front();
+            }
+            self.records.push_back(record);
+        }
+    }
+
+    pub fn undo_step(&mut self, memory: &mut Memory, keypad: &mut Keypad) -> bool {
+        let record = match self.records.pop_back() {
+            Some(record) => record,
+            None => return false,
+        };
+
+        memory.variable_register = record.variable_register;
+        memory.stack = record.stack;
+        memory.stack_pointer = record.stack_pointer;
+        memory.program_counter = record.program_counter;
+        memory.opcode = record.opcode;
+        memory.index_register = record.index_register;
+        memory.delay_timer = record.delay_timer;
+        memory.sound_timer = record.sound_timer;
+        memory.flag_register = record.flag_register;
+        memory.resolution = record.resolution;
+        memory.selected_planes = record.selected_planes;
+        memory.audio_pattern = record.audio_pattern;
+        memory.pitch = record.pitch;
+        memory.random_source.set_state(record.random_state);
+        keypad.set_keys(record.keys);
+
+        let stored_end = record.stored_address + record.stored_bytes.len();
+        memory.memory[record.stored_address..stored_end].copy_from_slice(&record.stored_bytes);
+
+        match record.display {
+            DisplayChange::Unchanged => {}
+            DisplayChange::Pixels(pixels) => {
+                for (index, value) in pixels {
+                    memory.graphic_array.set_pixel_at(index, value);
+                }
+            }
+            DisplayChange::Complete(graphic_array) => memory.graphic_array = graphic_array,
+        }
+
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+        self.pending = None;
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    fn compare_display(old_display: &FrameBuffer, new_display: &FrameBuffer) -> DisplayChange {
+        if old_display.get_columns() != new_display.get_columns()
+            || old_display.get_rows() != new_display.get_rows()
+        {
+            return DisplayChange::Complete(old_display.clone());
+        }
+
+        let pixels: Vec<(usize, u8)> = old_display
+            .get_pixels()
+            .iter()
+            .zip(new_display.get_pixels().iter())
+            .enumerate()
+            .filter(|(_, (old, new))| old != new)
+            .map(|(index, (old, _))| (index, *old))
+            .collect();
+
+        if pixels.is_empty() {
+            DisplayChange::Unchanged
+        } else {
+            DisplayChange::Pixels(pixels)
+        }
+    }
+}
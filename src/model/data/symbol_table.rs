@@ -0,0 +1,105 @@
+use crate::model::parse_number;
+
+use std::{
+    collections::{btree_map, BTreeMap, HashMap},
+    result,
+};
+
+#[derive(Clone, Default, Debug)]
+pub struct SymbolTable {
+    names: BTreeMap<usize, String>,
+    addresses: HashMap<String, usize>,
+}
+
+impl SymbolTable {
+    pub fn new() -> SymbolTable {
+        SymbolTable {
+            names: BTreeMap::new(),
+            addresses: HashMap::new(),
+        }
+    }
+
+    pub fn from_labels(labels: &BTreeMap<String, usize>) -> SymbolTable {
+        let mut symbols = SymbolTable::new();
+        for (name, address) in labels.iter() {
+            symbols.insert(name, *address);
+        }
+
+        symbols
+    }
+
+    // One symbol per line as "<name> = <address>", "<name> <address>" or
+    // "<address> <name>", ';' and a '#' that does not start a hex address begin a comment
+    pub fn parse(text: &str) -> result::Result<SymbolTable, String> {
+        let mut symbols = SymbolTable::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split(';').next().unwrap_or("").replace('=', " ");
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.is_empty() || (parts[0].starts_with('#') && parse_number(parts[0]).is_none()) {
+                continue;
+            }
+
+            let symbol = match parts.as_slice() {
+                [first, second] => match (parse_number(first), parse_number(second)) {
+                    (None, Some(address)) => Some((*first, address)),
+                    (Some(address), None) => Some((*second, address)),
+                    _ => None,
+                },
+                _ => None,
+            };
+            match symbol {
+                Some((name, address)) if SymbolTable::is_valid_name(name) => {
+                    symbols.insert(name, address)
+                }
+                _ => {
+                    return Err(format!(
+                        "Error: Invalid symbol on line {}: {}",
+                        number + 1,
+                        line.trim()
+                    ))
+                }
+            }
+        }
+
+        Ok(symbols)
+    }
+
+    // The first name given to an address is the one shown, every name resolves
+    pub fn insert(&mut self, name: &str, address: usize) {
+        self.addresses.insert(name.to_string(), address);
+        self.names
+            .entry(address)
+            .or_insert_with(|| name.to_string());
+    }
+
+    pub fn name(&self, address: usize) -> Option<&str> {
+        self.names.get(&address).map(|name| name.as_str())
+    }
+
+    pub fn address(&self, name: &str) -> Option<usize> {
+        self.addresses.get(name).cloned()
+    }
+
+    pub fn resolve(&self, text: &str) -> Option<usize> {
+        parse_number(text).or_else(|| self.address(text))
+    }
+
+    pub fn iter(&self) -> btree_map::Iter<'_, usize, String> {
+        self.names.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.addresses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
+
+    fn is_valid_name(name: &str) -> bool {
+        name.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+    }
+}
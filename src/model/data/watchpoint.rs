@@ -0,0 +1,86 @@
+use crate::defines::{MemoryOperation, WatchKind};
+use crate::model::SymbolTable;
+
+use std::result;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct MemoryEvent {
+    pub address: usize,
+    pub operation: MemoryOperation,
+    pub old_value: u8,
+    pub new_value: u8,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct WatchHit {
+    pub program_counter: usize,
+    pub event: MemoryEvent,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Watchpoint {
+    pub start: usize,
+    pub end: usize,
+    pub kind: WatchKind,
+    pub hit_count: u32,
+}
+
+impl Watchpoint {
+    pub fn new(start: usize, end: usize, kind: WatchKind) -> Watchpoint {
+        Watchpoint {
+            start,
+            end,
+            kind,
+            hit_count: 0,
+        }
+    }
+
+    // Text form "<start>[-<end>] [r|w|rw]", the range includes the end address
+    pub fn parse(text: &str, symbols: &SymbolTable) -> result::Result<Watchpoint, String> {
+        let mut parts = text.split_whitespace();
+        let range = parts
+            .next()
+            .ok_or("Error: The watchpoint needs an address".to_string())?;
+        let kind = match parts.next().map(|kind| kind.to_lowercase()) {
+            None => WatchKind::Write,
+            Some(ref kind) if kind == "r" => WatchKind::Read,
+            Some(ref kind) if kind == "w" => WatchKind::Write,
+            Some(ref kind) if kind == "rw" => WatchKind::ReadWrite,
+            Some(kind) => return Err(format!("Error: Unknown watchpoint type: {}", kind)),
+        };
+
+        // Symbol names may contain '-', a name that matches as a whole is not split
+        let (start, end) = match symbols.address(range) {
+            Some(address) => (address, address),
+            None => {
+                let mut bounds = range.splitn(2, '-');
+                let start = bounds.next().unwrap_or("");
+                let start = symbols
+                    .resolve(start)
+                    .ok_or(format!("Error: Invalid watchpoint address: {}", start))?;
+                let end = match bounds.next() {
+                    Some(end) => symbols
+                        .resolve(end)
+                        .ok_or(format!("Error: Invalid watchpoint address: {}", end))?,
+                    None => start,
+                };
+                (start, end)
+            }
+        };
+        if end < start {
+            return Err(format!("Error: Invalid watchpoint range: {}", range));
+        }
+
+        Ok(Watchpoint::new(start, end, kind))
+    }
+
+    pub fn matches(&self, event: &MemoryEvent) -> bool {
+        let operation_matches = match self.kind {
+            WatchKind::Read => event.operation == MemoryOperation::Read,
+            WatchKind::Write => event.operation == MemoryOperation::Write,
+            WatchKind::ReadWrite => true,
+        };
+
+        operation_matches && event.address >= self.start && event.address <= self.end
+    }
+}
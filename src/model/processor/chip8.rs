@@ -0,0 +1,236 @@
+use crate::controller::StateManager;
+use crate::defines::{
+    game_constants::{BASE_PROGRAM_SPEED, STEP_HISTORY_SIZE},
+    memory_constants::VARIABLES_COUNT,
+    CpuState, KeyPress, QuirkProfile, TimingMode,
+};
+use crate::model::{
+    vip_cycles, Cpu, CpuFault, FrameBuffer, Keypad, MachineState, Memory, Quirks, RandomSource,
+    States, StepHistory, VIP_CYCLE_BUDGET,
+};
+
+use std::sync::{Arc, Mutex};
+
+pub struct Chip8 {
+    cpu: Cpu,
+    memory: Arc<Mutex<Memory>>,
+    keypad: Arc<Mutex<Keypad>>,
+    quirks: Arc<Mutex<Quirks>>,
+    state_manager: Arc<Mutex<StateManager>>,
+    instructions_per_frame: u64,
+    timing_mode: TimingMode,
+    frame_instructions: u64,
+    frame_cycles: u64,
+    waiting_for_vblank: bool,
+    history: StepHistory,
+    record_history: bool,
+}
+
+impl Default for Chip8 {
+    fn default() -> Self {
+        Chip8::new()
+    }
+}
+
+impl Chip8 {
+    pub fn new() -> Chip8 {
+        let states = Arc::new(Mutex::new(States::new()));
+        Chip8::with_shared_state(
+            Arc::new(Mutex::new(Memory::new())),
+            Arc::new(Mutex::new(Keypad::new())),
+            Arc::new(Mutex::new(StateManager::new(states))),
+            Arc::new(Mutex::new(Quirks::default())),
+        )
+    }
+
+    pub fn with_shared_state(
+        new_memory: Arc<Mutex<Memory>>,
+        new_keypad: Arc<Mutex<Keypad>>,
+        new_state_manager: Arc<Mutex<StateManager>>,
+        new_quirks: Arc<Mutex<Quirks>>,
+    ) -> Chip8 {
+        let cpu = Cpu::new(
+            Arc::clone(&new_keypad),
+            Arc::clone(&new_memory),
+            Arc::clone(&new_state_manager),
+            Arc::clone(&new_quirks),
+        );
+
+        Chip8 {
+            cpu,
+            memory: new_memory,
+            keypad: new_keypad,
+            quirks: new_quirks,
+            state_manager: new_state_manager,
+            instructions_per_frame: BASE_PROGRAM_SPEED,
+            timing_mode: TimingMode::Flat,
+            frame_instructions: 0,
+            frame_cycles: 0,
+            waiting_for_vblank: false,
+            history: StepHistory::new(STEP_HISTORY_SIZE),
+            record_history: false,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.cpu.reset();
+        self.history.clear();
+        self.frame_cycles = 0;
+        self.begin_frame();
+    }
+
+    pub fn load_program(&mut self, code: &[u8]) -> Result<(), String> {
+        self.reset();
+        self.cpu.load_program_code(code)
+    }
+
+    pub fn step(&mut self) -> Result<(), CpuFault> {
+        let running = self.is_running();
+        let registers = self.memory.lock().unwrap().variable_register;
+        let result = if self.record_history {
+            self.history
+                .begin_step(&self.memory.lock().unwrap(), &self.keypad.lock().unwrap());
+            let result = self.cpu.run_opcode();
+            self.history.finish_step(&self.memory.lock().unwrap());
+            result
+        } else {
+            self.cpu.run_opcode()
+        };
+        if running && result.is_ok() {
+            self.count_step(&registers);
+        }
+
+        result
+    }
+
+    fn count_step(&mut self, registers: &[u8; VARIABLES_COUNT]) {
+        let memory = self.memory.lock().unwrap();
+        self.frame_instructions += 1;
+        if self.timing_mode == TimingMode::Vip {
+            self.frame_cycles += vip_cycles(&memory, registers);
+        }
+        if memory.opcode & 0xF000 == 0xD000 && self.quirks.lock().unwrap().display_wait {
+            self.waiting_for_vblank = true;
+        }
+    }
+
+    // Cycles spent past the budget of the last frame are taken from the next one
+    pub fn begin_frame(&mut self) {
+        if self.waiting_for_vblank || self.frame_cycles < VIP_CYCLE_BUDGET {
+            self.frame_cycles = 0;
+        } else {
+            self.frame_cycles -= VIP_CYCLE_BUDGET;
+        }
+        self.frame_instructions = 0;
+        self.waiting_for_vblank = false;
+    }
+
+    // A frame ends when its instructions or cycles are used up, when a sprite
+    // was drawn with the display wait quirk or when the program has exited
+    pub fn is_frame_finished(&mut self) -> bool {
+        let used_up = match self.timing_mode {
+            TimingMode::Flat => self.frame_instructions >= self.instructions_per_frame,
+            TimingMode::Vip => self.frame_cycles >= VIP_CYCLE_BUDGET,
+        };
+
+        used_up || self.waiting_for_vblank || !self.is_running()
+    }
+
+    pub fn step_back(&mut self) -> bool {
+        self.history.undo_step(
+            &mut self.memory.lock().unwrap(),
+            &mut self.keypad.lock().unwrap(),
+        )
+    }
+
+    pub fn set_history_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.history.clear();
+        }
+        self.record_history = enabled;
+    }
+
+    pub fn get_history_length(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn run_frame(&mut self) -> Result<(), CpuFault> {
+        self.begin_frame();
+        while !self.is_frame_finished() {
+            self.step()?;
+        }
+        self.tick_timers();
+
+        Ok(())
+    }
+
+    pub fn tick_timers(&mut self) {
+        self.cpu.tick_timer();
+    }
+
+    pub fn set_key(&mut self, key: u8, pressed: bool) {
+        let value = if pressed {
+            KeyPress::Down
+        } else {
+            KeyPress::Up
+        };
+        self.keypad.lock().unwrap().set_key(key, value);
+    }
+
+    pub fn framebuffer(&self) -> FrameBuffer {
+        self.memory.lock().unwrap().graphic_array.clone()
+    }
+
+    pub fn set_quirk_profile(&mut self, profile: QuirkProfile) {
+        self.quirks.lock().unwrap().set_profile(profile);
+    }
+
+    pub fn save_state(&mut self) -> MachineState {
+        MachineState::capture(
+            &self.memory.lock().unwrap(),
+            &self.keypad.lock().unwrap(),
+            &self.quirks.lock().unwrap(),
+            self.instructions_per_frame,
+            self.timing_mode,
+        )
+    }
+
+    pub fn load_state(&mut self, state: &MachineState) {
+        state.restore(
+            &mut self.memory.lock().unwrap(),
+            &mut self.keypad.lock().unwrap(),
+            &mut self.quirks.lock().unwrap(),
+        );
+        self.instructions_per_frame = state.speed;
+        self.timing_mode = state.timing_mode;
+        self.history.clear();
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.memory.lock().unwrap().set_seed(seed);
+    }
+
+    pub fn set_random_source(&mut self, random_source: Box<dyn RandomSource>) {
+        self.memory.lock().unwrap().random_source = random_source;
+    }
+
+    pub fn set_instructions_per_frame(&mut self, instructions: u64) {
+        self.instructions_per_frame = instructions;
+    }
+
+    pub fn set_timing_mode(&mut self, timing_mode: TimingMode) {
+        self.timing_mode = timing_mode;
+    }
+
+    pub fn get_frame_instructions(&self) -> u64 {
+        self.frame_instructions
+    }
+
+    pub fn is_sound_playing(&mut self) -> bool {
+        self.cpu.play_sound()
+    }
+
+    pub fn is_running(&mut self) -> bool {
+        self.state_manager.lock().unwrap().get_cpu_state() == CpuState::Running
+    }
+}
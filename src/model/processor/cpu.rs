@@ -1,20 +1,19 @@
 use crate::defines::{
     memory_constants::{
-        BIG_SPRITE, CARRY_FLAG, COLUMNS, FLAG_REGISTER_SIZE, GRAPHIC_SIZE, GRAPHIC_SIZE_HIGH,
-        MAX_PROGRAM_SIZE, MEMORYSIZE, PROGRAM_START, PROGRAM_STEP, ROWS, SCROLL_RANGE,
-        SPRITE_WIDTH, STACKSIZE, VARIABLES_COUNT,
+        AUDIO_PATTERN_SIZE, BIG_SPRITE, CARRY_FLAG, COLUMNS, FLAG_REGISTER_SIZE,
+        LONG_INSTRUCTION_SIZE, MAX_PROGRAM_SIZE, MEMORYSIZE, PROGRAM_START, PROGRAM_STEP, ROWS,
+        SCROLL_RANGE, SPRITE_WIDTH, STACKSIZE, VARIABLES_COUNT,
     },
-    CpuState, KeyPress, Reset,
+    CpuState, IndexIncrement, KeyPress,
 };
 
 use crate::model::{
-    Keypad, Memory, Resolution, FONTSET_HIGH, FONTSET_HIGH_SIZE, FONTSET_HIGH_START, FONTSET_LOW,
-    FONTSET_LOW_SIZE,
+    CpuFault, FrameBuffer, Keypad, Memory, Quirks, Resolution, FONTSET_HIGH, FONTSET_HIGH_SIZE,
+    FONTSET_HIGH_START, FONTSET_LOW, FONTSET_LOW_SIZE,
 };
 
 use controller::StateManager;
 
-use rand::Rng;
 use std::sync::{Arc, Mutex};
 
 #[derive(Copy, Clone, PartialEq)]
@@ -29,13 +28,12 @@ pub struct Cpu {
     data_ref: Arc<Mutex<Memory>>,
     keypad: Arc<Mutex<Keypad>>,
     state_manager: Arc<Mutex<StateManager>>,
+    quirks: Arc<Mutex<Quirks>>,
     x: usize,
     y: usize,
     nnn: u16,
     kk: u8,
     n: usize,
-    max_columns: usize,
-    max_rows: usize,
 }
 
 impl Cpu {
@@ -43,6 +41,7 @@ impl Cpu {
         new_keypad: Arc<Mutex<Keypad>>,
         new_data: Arc<Mutex<Memory>>,
         new_states_manager: Arc<Mutex<StateManager>>,
+        new_quirks: Arc<Mutex<Quirks>>,
     ) -> Cpu {
         new_data.lock().unwrap().memory[..FONTSET_LOW_SIZE].copy_from_slice(&FONTSET_LOW[..]);
         new_data.lock().unwrap().memory[FONTSET_HIGH_START..FONTSET_HIGH_START + FONTSET_HIGH_SIZE]
@@ -52,13 +51,12 @@ impl Cpu {
             data_ref: new_data,
             keypad: new_keypad,
             state_manager: new_states_manager,
+            quirks: new_quirks,
             x: 0,
             y: 0,
             nnn: 0,
             kk: 0,
             n: 0,
-            max_columns: COLUMNS,
-            max_rows: ROWS,
         }
     }
 
@@ -67,8 +65,6 @@ impl Cpu {
             .lock()
             .unwrap()
             .update_cpu_state(CpuState::Running);
-        self.max_columns = COLUMNS;
-        self.max_rows = ROWS;
 
         let mut data = self.data_ref.lock().unwrap();
         data.reset();
@@ -77,24 +73,53 @@ impl Cpu {
             .copy_from_slice(&FONTSET_HIGH[..]);
     }
 
-    pub fn load_program_code(&mut self, code: &[u8]) {
-        let mut data = self.data_ref.lock().unwrap();
+    pub fn load_program_code(&mut self, code: &[u8]) -> Result<(), String> {
         let code_size = code.len();
-        data.memory[PROGRAM_START..PROGRAM_START + code_size].copy_from_slice(&code);
+        if code_size > MAX_PROGRAM_SIZE {
+            return Err(format!(
+                "Error: The program has {} bytes, at most {} fit into memory",
+                code_size, MAX_PROGRAM_SIZE
+            ));
+        }
+
+        let mut data = self.data_ref.lock().unwrap();
+        data.memory[PROGRAM_START..PROGRAM_START + code_size].copy_from_slice(code);
+        Ok(())
     }
 
-    fn set_opcode(&mut self) {
+    fn set_opcode(&mut self) -> Result<(), CpuFault> {
         let mut data = self.data_ref.lock().unwrap();
+        data.instruction_address = data.program_counter;
+        data.fault = None;
+        if data.program_counter + 1 >= MEMORYSIZE {
+            return Err(CpuFault::ProgramCounterOutOfRange {
+                address: data.program_counter,
+            });
+        }
         data.opcode = (data.memory[data.program_counter] as u16) << 8
             | (data.memory[data.program_counter + 1] as u16);
+
+        Ok(())
     }
 
-    pub fn run_opcode(&mut self) {
+    pub fn run_opcode(&mut self) -> Result<(), CpuFault> {
         if self.state_manager.lock().unwrap().get_cpu_state() == CpuState::Running {
-            self.set_opcode();
-            let nibbles = self.decode_opcode();
-            self.match_opcode(nibbles);
+            let result = self.execute_opcode();
+            if let Err(fault) = result {
+                let mut data = self.data_ref.lock().unwrap();
+                data.program_counter = data.instruction_address;
+                data.fault = Some(fault);
+            }
+            return result;
         }
+
+        Ok(())
+    }
+
+    fn execute_opcode(&mut self) -> Result<(), CpuFault> {
+        self.set_opcode()?;
+        let nibbles = self.decode_opcode();
+        self.match_opcode(nibbles)
     }
 
     pub fn tick_timer(&mut self) {
@@ -115,23 +140,40 @@ impl Cpu {
     fn print_graphic_array(&mut self) {
         let graphic_array = self.data_ref.lock().unwrap().graphic_array.clone();
 
-        for (i, iter) in graphic_array.iter().enumerate() {
-            if i % self.max_columns == 0 {
+        for (i, iter) in graphic_array.get_pixels().iter().enumerate() {
+            if i % graphic_array.get_columns() == 0 {
                 println!("");
             }
             print!("{}", iter);
         }
     }
 
-    fn no_match(&mut self) {
-        self.state_manager
-            .lock()
-            .unwrap()
-            .update_cpu_state(CpuState::Stopped);
-        println!(
-            "Error: No matching opcode: {:04X}",
-            self.data_ref.lock().unwrap().opcode
-        );
+    fn skip_instruction(data: &mut Memory) {
+        let address = data.program_counter;
+        if data.memory.get(address) == Some(&0xF0) && data.memory.get(address + 1) == Some(&0x00) {
+            data.program_counter += LONG_INSTRUCTION_SIZE;
+        } else {
+            data.program_counter += PROGRAM_STEP;
+        }
+    }
+
+    fn no_match(&mut self) -> Result<(), CpuFault> {
+        let data = self.data_ref.lock().unwrap();
+        Err(CpuFault::InvalidOpcode {
+            address: data.instruction_address,
+            opcode: data.opcode,
+        })
+    }
+
+    fn check_memory(data: &Memory, start: usize, length: usize) -> Result<(), CpuFault> {
+        if start + length > MEMORYSIZE {
+            return Err(CpuFault::MemoryOutOfBounds {
+                address: data.instruction_address,
+                target: start + length - 1,
+            });
+        }
+
+        Ok(())
     }
 
     fn decode_opcode(&mut self) -> (u16, u16, u16, u16) {
@@ -155,22 +197,25 @@ impl Cpu {
         nibbles
     }
 
-    fn match_opcode(&mut self, nibbles: (u16, u16, u16, u16)) {
+    fn match_opcode(&mut self, nibbles: (u16, u16, u16, u16)) -> Result<(), CpuFault> {
         match nibbles {
             (0x0, 0x0, 0xb, _) => self.op_00bn(),
             (0x0, 0x0, 0xc, _) => self.op_00cn(),
+            (0x0, 0x0, 0xd, _) => self.op_00dn(),
             (0x0, 0x0, 0xe, 0x0) => self.op_00e0(),
-            (0x0, 0x0, 0xe, 0xe) => self.op_00ee(),
+            (0x0, 0x0, 0xe, 0xe) => self.op_00ee()?,
             (0x0, 0x0, 0xf, 0xb) => self.op_00fb(),
             (0x0, 0x0, 0xf, 0xc) => self.op_00fc(),
             (0x0, 0x0, 0xf, 0xd) => self.op_00fd(),
             (0x0, 0x0, 0xf, 0xe) => self.op_00fe(),
             (0x0, 0x0, 0xf, 0xf) => self.op_00ff(),
             (0x1, _, _, _) => self.op_1nnn(),
-            (0x2, _, _, _) => self.op_2nnn(),
+            (0x2, _, _, _) => self.op_2nnn()?,
             (0x3, _, _, _) => self.op_3xkk(),
             (0x4, _, _, _) => self.op_4xkk(),
             (0x5, _, _, 0x0) => self.op_5xy0(),
+            (0x5, _, _, 0x2) => self.op_5xy2()?,
+            (0x5, _, _, 0x3) => self.op_5xy3()?,
             (0x6, _, _, _) => self.op_6xkk(),
             (0x7, _, _, _) => self.op_7xkk(),
             (0x8, _, _, 0x0) => self.op_8xy0(),
@@ -186,10 +231,13 @@ impl Cpu {
             (0xA, _, _, _) => self.op_annn(),
             (0xB, _, _, _) => self.op_bnnn(),
             (0xC, _, _, _) => self.op_cxkk(),
-            (0xD, _, _, 0x0) => self.op_dxy0(),
-            (0xD, _, _, _) => self.op_dxyn(),
+            (0xD, _, _, 0x0) => self.op_dxy0()?,
+            (0xD, _, _, _) => self.op_dxyn()?,
             (0xE, _, 0x9, 0xE) => self.op_ex9e(),
             (0xE, _, 0xA, 0x1) => self.op_exa1(),
+            (0xF, 0x0, 0x0, 0x0) => self.op_f000()?,
+            (0xF, _, 0x0, 0x1) => self.op_fn01(),
+            (0xF, 0x0, 0x0, 0x2) => self.op_f002()?,
             (0xF, _, 0x0, 0x7) => self.op_fx07(),
             (0xF, _, 0x0, 0xA) => self.op_fx0a(),
             (0xF, _, 0x1, 0x5) => self.op_fx15(),
@@ -197,77 +245,76 @@ impl Cpu {
             (0xF, _, 0x1, 0xE) => self.op_fx1e(),
             (0xF, _, 0x2, 0x9) => self.op_fx29(),
             (0xF, _, 0x3, 0x0) => self.op_fx30(),
-            (0xF, _, 0x3, 0x3) => self.op_fx33(),
-            (0xF, _, 0x5, 0x5) => self.op_fx55(),
-            (0xF, _, 0x6, 0x5) => self.op_fx65(),
+            (0xF, _, 0x3, 0x3) => self.op_fx33()?,
+            (0xF, _, 0x3, 0xA) => self.op_fx3a(),
+            (0xF, _, 0x5, 0x5) => self.op_fx55()?,
+            (0xF, _, 0x6, 0x5) => self.op_fx65()?,
             (0xF, _, 0x7, 0x5) => self.op_fx75(),
             (0xF, _, 0x8, 0x5) => self.op_fx85(),
-            _ => self.no_match(),
+            _ => self.no_match()?,
         }
+
+        Ok(())
     }
 
     //Scroll Up
     fn op_00bn(&mut self) {
-        let mut data = self.data_ref.lock().unwrap();
-        let graphic_copy = data.graphic_array.clone();
-        let graphic_size = graphic_copy.len();
-        data.graphic_array.reset_all();
-        let shift: usize = self.n * COLUMNS * data.resolution as usize;
-        data.graphic_array[..graphic_size - shift].copy_from_slice(&graphic_copy[shift..]);
+        self.scroll_up();
     }
 
     //Scroll Down
     fn op_00cn(&mut self) {
         let mut data = self.data_ref.lock().unwrap();
-        let graphic_copy = data.graphic_array.clone();
-        data.graphic_array.reset_all();
-        let graphic_size = graphic_copy.len();
-        let shift: usize = self.n * COLUMNS * data.resolution as usize;
-        data.graphic_array[shift..].copy_from_slice(&graphic_copy[..graphic_size - shift]);
+        let planes = data.selected_planes;
+        data.graphic_array.scroll_down(self.n, planes);
+    }
+
+    //Scroll Up (XO-CHIP)
+    fn op_00dn(&mut self) {
+        self.scroll_up();
+    }
+
+    fn scroll_up(&mut self) {
+        let mut data = self.data_ref.lock().unwrap();
+        let planes = data.selected_planes;
+        data.graphic_array.scroll_up(self.n, planes);
     }
 
     //CLS
     fn op_00e0(&mut self) {
-        self.data_ref.lock().unwrap().graphic_array.reset_all();
+        let mut data = self.data_ref.lock().unwrap();
+        let planes = data.selected_planes;
+        data.graphic_array.clear(planes);
     }
 
     //RET from subroutine
-    fn op_00ee(&mut self) {
+    fn op_00ee(&mut self) -> Result<(), CpuFault> {
         let mut data = self.data_ref.lock().unwrap();
+        if data.stack_pointer == 0 || data.stack_pointer > STACKSIZE {
+            return Err(CpuFault::StackUnderflow {
+                address: data.instruction_address,
+            });
+        }
         data.stack_pointer -= 1;
         let stack_pointer = data.stack_pointer;
         data.program_counter = data.stack[stack_pointer] as usize;
         data.stack[stack_pointer] = 0;
+
+        Ok(())
     }
 
     //Scroll Right
     fn op_00fb(&mut self) {
         let mut data = self.data_ref.lock().unwrap();
-        let graphic_copy = data.graphic_array.clone();
-
-        for (i, pixel) in data.graphic_array.iter_mut().enumerate() {
-            if i % self.max_columns < SCROLL_RANGE + 1 {
-                *pixel = 0;
-            } else {
-                *pixel = graphic_copy[i - SCROLL_RANGE];
-            }
-        }
+        let planes = data.selected_planes;
+        data.graphic_array.scroll_right(SCROLL_RANGE, planes);
     }
 
     //Scroll Left
     fn op_00fc(&mut self) {
         let mut data = self.data_ref.lock().unwrap();
-        let graphic_copy = data.graphic_array.clone();
-
-        for (i, pixel) in data.graphic_array.iter_mut().enumerate() {
-            if i % self.max_columns > self.max_columns - SCROLL_RANGE
-                || i + SCROLL_RANGE >= graphic_copy.len()
-            {
-                *pixel = 0;
-            } else {
-                *pixel = graphic_copy[i + SCROLL_RANGE];
-            }
-        }
+        let planes = data.selected_planes;
+        data.graphic_array.scroll_left(SCROLL_RANGE, planes);
     }
 
     //Exit
@@ -281,38 +328,19 @@ impl Cpu {
 
     //Low Res
     fn op_00fe(&mut self) {
-        let mut data = self.data_ref.lock().unwrap();
-        let data_copy = data.graphic_array.clone();
-        let res_factor = data.resolution as usize;
-        data.resolution = Resolution::Low;
-        data.graphic_array = vec![0; GRAPHIC_SIZE];
-        self.max_columns = COLUMNS * Resolution::Low as usize;
-        self.max_rows = ROWS * Resolution::Low as usize;
-
-        for x in (0..self.max_columns).step_by(res_factor) {
-            for y in (0..self.max_rows).step_by(res_factor) {
-                data.graphic_array[(x / res_factor) + (y / res_factor) * COLUMNS] =
-                    data_copy[x + y * self.max_columns];
-            }
-        }
+        self.set_resolution(Resolution::Low);
     }
 
     //High Res
     fn op_00ff(&mut self) {
+        self.set_resolution(Resolution::High);
+    }
+
+    fn set_resolution(&mut self, resolution: Resolution) {
         let mut data = self.data_ref.lock().unwrap();
-        let data_copy = data.graphic_array.clone();
-        let res_factor = Resolution::High as usize;
-        data.resolution = Resolution::High;
-        data.graphic_array = vec![0; GRAPHIC_SIZE_HIGH];
-        self.max_columns = COLUMNS * Resolution::High as usize;
-        self.max_rows = ROWS * Resolution::High as usize;
-
-        for x in 0..self.max_columns {
-            for y in 0..self.max_rows {
-                data.graphic_array[x + y * self.max_columns] =
-                    data_copy[(x / res_factor) + (y / res_factor) * COLUMNS];
-            }
-        }
+        data.resolution = resolution;
+        data.graphic_array
+            .resize(COLUMNS * resolution as usize, ROWS * resolution as usize);
     }
 
     //JP addr
@@ -322,19 +350,26 @@ impl Cpu {
     }
 
     //CALL addr
-    fn op_2nnn(&mut self) {
+    fn op_2nnn(&mut self) -> Result<(), CpuFault> {
         let mut data = self.data_ref.lock().unwrap();
+        if data.stack_pointer >= STACKSIZE {
+            return Err(CpuFault::StackOverflow {
+                address: data.instruction_address,
+            });
+        }
         let stack_pointer = data.stack_pointer;
         data.stack[stack_pointer] = data.program_counter as u16;
         data.stack_pointer += 1;
         data.program_counter = self.nnn as usize;
+
+        Ok(())
     }
 
     //SE Vx, byte
     fn op_3xkk(&mut self) {
         let mut data = self.data_ref.lock().unwrap();
         if data.variable_register[self.x] == self.kk {
-            data.program_counter += PROGRAM_STEP;
+            Cpu::skip_instruction(&mut data);
         }
     }
 
@@ -342,7 +377,7 @@ impl Cpu {
     fn op_4xkk(&mut self) {
         let mut data = self.data_ref.lock().unwrap();
         if data.variable_register[self.x] != self.kk {
-            data.program_counter += PROGRAM_STEP;
+            Cpu::skip_instruction(&mut data);
         }
     }
 
@@ -350,7 +385,48 @@ impl Cpu {
     fn op_5xy0(&mut self) {
         let mut data = self.data_ref.lock().unwrap();
         if data.variable_register[self.x] == data.variable_register[self.y] {
-            data.program_counter += PROGRAM_STEP;
+            Cpu::skip_instruction(&mut data);
+        }
+    }
+
+    //SAVE Vx - Vy
+    fn op_5xy2(&mut self) -> Result<(), CpuFault> {
+        let mut data = self.data_ref.lock().unwrap();
+        let index = data.index_register as usize;
+        Cpu::check_memory(&data, index, Cpu::register_count(self.x, self.y))?;
+        for (offset, register) in Cpu::register_range(self.x, self.y).enumerate() {
+            let value = data.variable_register[register];
+            data.write_byte(index + offset, value);
+        }
+
+        Ok(())
+    }
+
+    //LOAD Vx - Vy
+    fn op_5xy3(&mut self) -> Result<(), CpuFault> {
+        let mut data = self.data_ref.lock().unwrap();
+        let index = data.index_register as usize;
+        Cpu::check_memory(&data, index, Cpu::register_count(self.x, self.y))?;
+        for (offset, register) in Cpu::register_range(self.x, self.y).enumerate() {
+            data.variable_register[register] = data.read_byte(index + offset);
+        }
+
+        Ok(())
+    }
+
+    fn register_count(x: usize, y: usize) -> usize {
+        if x <= y {
+            y - x + 1
+        } else {
+            x - y + 1
+        }
+    }
+
+    fn register_range(x: usize, y: usize) -> Box<dyn Iterator<Item = usize>> {
+        if x <= y {
+            Box::new(x..y + 1)
+        } else {
+            Box::new((y..x + 1).rev())
         }
     }
 
@@ -376,18 +452,27 @@ impl Cpu {
     fn op_8xy1(&mut self) {
         let mut data = self.data_ref.lock().unwrap();
         data.variable_register[self.x] |= data.variable_register[self.y];
+        if self.quirks.lock().unwrap().vf_reset {
+            data.variable_register[CARRY_FLAG] = 0;
+        }
     }
 
     //AND Vx, Vy
     fn op_8xy2(&mut self) {
         let mut data = self.data_ref.lock().unwrap();
         data.variable_register[self.x] &= data.variable_register[self.y];
+        if self.quirks.lock().unwrap().vf_reset {
+            data.variable_register[CARRY_FLAG] = 0;
+        }
     }
 
     //XOR Vx, Vy
     fn op_8xy3(&mut self) {
         let mut data = self.data_ref.lock().unwrap();
         data.variable_register[self.x] ^= data.variable_register[self.y];
+        if self.quirks.lock().unwrap().vf_reset {
+            data.variable_register[CARRY_FLAG] = 0;
+        }
     }
 
     //ADD Vx, Vy
@@ -412,9 +497,11 @@ impl Cpu {
     //SHR Vx
     fn op_8xy6(&mut self) {
         let mut data = self.data_ref.lock().unwrap();
+        let source = self.shift_source();
+        let value = data.variable_register[source];
 
-        data.variable_register[CARRY_FLAG] = data.variable_register[self.x] & 0x1;
-        data.variable_register[self.x] >>= 1;
+        data.variable_register[self.x] = value >> 1;
+        data.variable_register[CARRY_FLAG] = value & 0x1;
     }
 
     //SUBN Vx, Vy
@@ -429,15 +516,26 @@ impl Cpu {
     //SHL Vx
     fn op_8xye(&mut self) {
         let mut data = self.data_ref.lock().unwrap();
-        data.variable_register[CARRY_FLAG] = data.variable_register[self.x] >> 7;
-        data.variable_register[self.x] <<= 1;
+        let source = self.shift_source();
+        let value = data.variable_register[source];
+
+        data.variable_register[self.x] = value << 1;
+        data.variable_register[CARRY_FLAG] = value >> 7;
+    }
+
+    fn shift_source(&self) -> usize {
+        if self.quirks.lock().unwrap().shift_uses_vy {
+            self.y
+        } else {
+            self.x
+        }
     }
 
     //SNE Vx, Vy
     fn op_9xy0(&mut self) {
         let mut data = self.data_ref.lock().unwrap();
         if data.variable_register[self.x] != data.variable_register[self.y] {
-            data.program_counter += PROGRAM_STEP;
+            Cpu::skip_instruction(&mut data);
         }
     }
 
@@ -450,70 +548,28 @@ impl Cpu {
     //JP V0, addr
     fn op_bnnn(&mut self) {
         let mut data = self.data_ref.lock().unwrap();
-        data.program_counter = (self.nnn + data.variable_register[0] as u16) as usize;
+        let register = if self.quirks.lock().unwrap().jump_uses_vx {
+            self.x
+        } else {
+            0
+        };
+        data.program_counter = (self.nnn + data.variable_register[register] as u16) as usize;
     }
 
     //RND Vx, byte
     fn op_cxkk(&mut self) {
         let mut data = self.data_ref.lock().unwrap();
-        let mut rng = rand::thread_rng();
-        data.variable_register[self.x] = rng.gen_range(0..0xFF + 1) as u8 & self.kk;
+        data.variable_register[self.x] = data.random_source.next_byte() & self.kk;
     }
 
     //DRW Vx, Vy, nibble
-    fn op_dxyn(&mut self) {
-        let mut data = self.data_ref.lock().unwrap();
-        let mut x_coordinate: usize;
-        let mut y_coordinate: usize;
-        let mut sprite: u8;
-
-        data.variable_register[CARRY_FLAG] = BitState::UNSET;
-        for row in 0..self.n as usize {
-            y_coordinate = (data.variable_register[self.y] as usize + row) % self.max_rows;
-            sprite = data.memory[data.index_register as usize + row];
-            for column in 0..SPRITE_WIDTH {
-                x_coordinate =
-                    (data.variable_register[self.x] as usize + column) % self.max_columns;
-                if (sprite & (0x80 >> column)) != BitState::UNSET {
-                    if data.graphic_array[(y_coordinate * self.max_columns) + x_coordinate]
-                        == BitState::SET
-                    {
-                        data.variable_register[CARRY_FLAG] = BitState::SET;
-                    }
-                    data.graphic_array[(y_coordinate * self.max_columns) + x_coordinate] ^=
-                        BitState::SET;
-                }
-            }
-        }
+    fn op_dxyn(&mut self) -> Result<(), CpuFault> {
+        self.draw_sprite(SPRITE_WIDTH, self.n)
     }
 
     //DRW 16x16
-    fn op_dxy0(&mut self) {
-        let mut data = self.data_ref.lock().unwrap();
-        let mut x_coordinate: usize;
-        let mut y_coordinate: usize;
-        let mut sprite: u16;
-
-        data.variable_register[CARRY_FLAG] = BitState::UNSET;
-        for row in (0..BIG_SPRITE * 2).step_by(2) {
-            y_coordinate = (data.variable_register[self.y] as usize + row) % self.max_rows;
-            sprite = (data.memory[data.index_register as usize + row] as u16) << 8
-                | data.memory[data.index_register as usize + row + 1] as u16;
-
-            for column in 0..BIG_SPRITE {
-                x_coordinate =
-                    (data.variable_register[self.x] as usize + column) % self.max_columns;
-                if (sprite & (0x8000 >> column)) != BitState::UNSET as u16 {
-                    if data.graphic_array[(y_coordinate * self.max_columns) + x_coordinate]
-                        == BitState::SET
-                    {
-                        data.variable_register[CARRY_FLAG] = BitState::SET;
-                    }
-                    data.graphic_array[(y_coordinate * self.max_columns) + x_coordinate] ^=
-                        BitState::SET;
-                }
-            }
-        }
+    fn op_dxy0(&mut self) -> Result<(), CpuFault> {
+        self.draw_sprite(BIG_SPRITE, BIG_SPRITE)
     }
 
     //SKP Vx
@@ -521,7 +577,7 @@ impl Cpu {
         let mut data = self.data_ref.lock().unwrap();
         let mut keypad_borrow = self.keypad.lock().unwrap();
         if keypad_borrow.get_key(data.variable_register[self.x]) == KeyPress::Down as u8 {
-            data.program_counter += PROGRAM_STEP;
+            Cpu::skip_instruction(&mut data);
             keypad_borrow.reset_key(data.variable_register[self.x]);
         }
     }
@@ -531,11 +587,42 @@ impl Cpu {
         let mut data = self.data_ref.lock().unwrap();
         let mut keypad_borrow = self.keypad.lock().unwrap();
         if keypad_borrow.get_key(data.variable_register[self.x]) == KeyPress::Up as u8 {
-            data.program_counter += PROGRAM_STEP;
+            Cpu::skip_instruction(&mut data);
         }
         keypad_borrow.reset_key(data.variable_register[self.x]);
     }
 
+    //LD I, long addr
+    fn op_f000(&mut self) -> Result<(), CpuFault> {
+        let mut data = self.data_ref.lock().unwrap();
+        let address = data.program_counter;
+        Cpu::check_memory(&data, address, PROGRAM_STEP)?;
+        data.index_register = (data.memory[address] as u16) << 8 | data.memory[address + 1] as u16;
+        data.program_counter += PROGRAM_STEP;
+
+        Ok(())
+    }
+
+    //PLANE n
+    fn op_fn01(&mut self) {
+        let mut data = self.data_ref.lock().unwrap();
+        data.selected_planes = self.x as u8;
+    }
+
+    //AUDIO
+    fn op_f002(&mut self) -> Result<(), CpuFault> {
+        let mut data = self.data_ref.lock().unwrap();
+        let index = data.index_register as usize;
+        Cpu::check_memory(&data, index, AUDIO_PATTERN_SIZE)?;
+        let mut pattern = [0; AUDIO_PATTERN_SIZE];
+        for (offset, value) in pattern.iter_mut().enumerate() {
+            *value = data.read_byte(index + offset);
+        }
+        data.audio_pattern = Some(pattern);
+
+        Ok(())
+    }
+
     //LD Vx, DT
     fn op_fx07(&mut self) {
         let mut data = self.data_ref.lock().unwrap();
@@ -569,7 +656,9 @@ impl Cpu {
     //LD ADD I, Vx
     fn op_fx1e(&mut self) {
         let mut data = self.data_ref.lock().unwrap();
-        data.index_register += data.variable_register[self.x] as u16;
+        data.index_register = data
+            .index_register
+            .wrapping_add(data.variable_register[self.x] as u16);
     }
 
     //LD F, Vx
@@ -586,31 +675,60 @@ impl Cpu {
     }
 
     //LD B, Vx
-    fn op_fx33(&mut self) {
+    fn op_fx33(&mut self) -> Result<(), CpuFault> {
         let mut data = self.data_ref.lock().unwrap();
         let index = data.index_register as usize;
-        data.memory[index] = data.variable_register[self.x] / 100;
-        data.memory[index + 1] = (data.variable_register[self.x] / 10) % 10;
-        data.memory[index + 2] = data.variable_register[self.x] % 10;
+        Cpu::check_memory(&data, index, 3)?;
+        let value = data.variable_register[self.x];
+        data.write_byte(index, value / 100);
+        data.write_byte(index + 1, (value / 10) % 10);
+        data.write_byte(index + 2, value % 10);
+
+        Ok(())
+    }
+
+    //PITCH Vx
+    fn op_fx3a(&mut self) {
+        let mut data = self.data_ref.lock().unwrap();
+        data.pitch = data.variable_register[self.x];
     }
 
     //LD [I], Vx
-    fn op_fx55(&mut self) {
+    fn op_fx55(&mut self) -> Result<(), CpuFault> {
         let mut data = self.data_ref.lock().unwrap();
         let index = data.index_register as usize;
+        Cpu::check_memory(&data, index, self.x + 1)?;
         for i in 0..self.x + 1 {
-            data.memory[index + i] = data.variable_register[i];
+            let value = data.variable_register[i];
+            data.write_byte(index + i, value);
         }
+        data.index_register = data
+            .index_register
+            .wrapping_add(self.load_store_increment());
+
+        Ok(())
     }
 
     //LD Vx, [I]
-    fn op_fx65(&mut self) {
+    fn op_fx65(&mut self) -> Result<(), CpuFault> {
         let mut data = self.data_ref.lock().unwrap();
         let index = data.index_register as usize;
-        if index + self.x < MEMORYSIZE {
-            for i in 0..self.x + 1 {
-                data.variable_register[i] = data.memory[index + i];
-            }
+        Cpu::check_memory(&data, index, self.x + 1)?;
+        for i in 0..self.x + 1 {
+            data.variable_register[i] = data.read_byte(index + i);
+        }
+        data.index_register = data
+            .index_register
+            .wrapping_add(self.load_store_increment());
+
+        Ok(())
+    }
+
+    fn load_store_increment(&self) -> u16 {
+        match self.quirks.lock().unwrap().load_store_increment {
+            IndexIncrement::Unchanged => 0,
+            IndexIncrement::ByX => self.x as u16,
+            IndexIncrement::ByXPlusOne => self.x as u16 + 1,
         }
     }
 
@@ -638,32 +756,61 @@ impl Cpu {
         }
     }
 
-    fn draw_sprite(&mut self, _sprite_size: u8) {
+    fn draw_sprite(&mut self, width: usize, height: usize) -> Result<(), CpuFault> {
         let mut data = self.data_ref.lock().unwrap();
+        let clip_sprites = self.quirks.lock().unwrap().clip_sprites;
+        let bytes_per_row = width / SPRITE_WIDTH;
+        let plane_count = FrameBuffer::planes(data.selected_planes).count();
+        let index = data.index_register as usize;
+        Cpu::check_memory(&data, index, plane_count * height * bytes_per_row)?;
+        let max_columns = data.graphic_array.get_columns();
+        let max_rows = data.graphic_array.get_rows();
+        let start_x = data.variable_register[self.x] as usize % max_columns;
+        let start_y = data.variable_register[self.y] as usize % max_rows;
+        let bitmask: u16 = 0x1 << (width - 1);
+        let mut sprite_address = data.index_register as usize;
         let mut x_coordinate: usize;
         let mut y_coordinate: usize;
-        let height = BIG_SPRITE;
-        let width = SPRITE_WIDTH;
-        let bitmask: u16 = 0x80;
         let mut sprite: u16;
 
         data.variable_register[CARRY_FLAG] = BitState::UNSET;
-        for row in 0..height as usize {
-            y_coordinate = (data.variable_register[self.y] as usize + row) % self.max_rows;
-            sprite = data.memory[data.index_register as usize + row] as u16;
-            for column in 0..width {
-                x_coordinate =
-                    (data.variable_register[self.x] as usize + column) % self.max_columns;
-                if (sprite & (bitmask >> column)) != BitState::UNSET as u16 {
-                    if data.graphic_array[(y_coordinate * self.max_columns) + x_coordinate]
-                        == BitState::SET
+        for plane in FrameBuffer::planes(data.selected_planes) {
+            for row in 0..height {
+                y_coordinate = start_y + row;
+                if y_coordinate >= max_rows {
+                    if clip_sprites {
+                        break;
+                    }
+                    y_coordinate %= max_rows;
+                }
+
+                let row_address = sprite_address + row * bytes_per_row;
+                sprite = 0;
+                for byte in 0..bytes_per_row {
+                    sprite = sprite << 8 | data.read_byte(row_address + byte) as u16;
+                }
+
+                for column in 0..width {
+                    x_coordinate = start_x + column;
+                    if x_coordinate >= max_columns {
+                        if clip_sprites {
+                            break;
+                        }
+                        x_coordinate %= max_columns;
+                    }
+
+                    if (sprite & (bitmask >> column)) != BitState::UNSET as u16
+                        && data
+                            .graphic_array
+                            .toggle_pixel(x_coordinate, y_coordinate, plane)
                     {
                         data.variable_register[CARRY_FLAG] = BitState::SET;
                     }
-                    data.graphic_array[(y_coordinate * self.max_columns) + x_coordinate] ^=
-                        BitState::SET;
                 }
             }
+            sprite_address += height * bytes_per_row;
         }
+
+        Ok(())
     }
 }
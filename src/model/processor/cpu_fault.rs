@@ -0,0 +1,60 @@
+use std::fmt;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum CpuFault {
+    InvalidOpcode { address: usize, opcode: u16 },
+    StackOverflow { address: usize },
+    StackUnderflow { address: usize },
+    MemoryOutOfBounds { address: usize, target: usize },
+    ProgramCounterOutOfRange { address: usize },
+}
+
+impl CpuFault {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CpuFault::InvalidOpcode { .. } => "Invalid Opcode",
+            CpuFault::StackOverflow { .. } => "Stack Overflow",
+            CpuFault::StackUnderflow { .. } => "Stack Underflow",
+            CpuFault::MemoryOutOfBounds { .. } => "Memory Access",
+            CpuFault::ProgramCounterOutOfRange { .. } => "PC Out Of Range",
+        }
+    }
+
+    pub fn address(&self) -> usize {
+        match *self {
+            CpuFault::InvalidOpcode { address, .. }
+            | CpuFault::StackOverflow { address }
+            | CpuFault::StackUnderflow { address }
+            | CpuFault::MemoryOutOfBounds { address, .. }
+            | CpuFault::ProgramCounterOutOfRange { address } => address,
+        }
+    }
+
+    pub fn is_stack_fault(&self) -> bool {
+        matches!(
+            self,
+            CpuFault::StackOverflow { .. } | CpuFault::StackUnderflow { .. }
+        )
+    }
+}
+
+impl fmt::Display for CpuFault {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CpuFault::InvalidOpcode { address, opcode } => write!(
+                formatter,
+                "No matching opcode: {:04X} at {:04X}",
+                opcode, address
+            ),
+            CpuFault::MemoryOutOfBounds { address, target } => write!(
+                formatter,
+                "Memory access out of range: {:X} at {:04X}",
+                target, address
+            ),
+            CpuFault::ProgramCounterOutOfRange { address } => {
+                write!(formatter, "Program counter out of range: {:X}", address)
+            }
+            _ => write!(formatter, "{} at {:04X}", self.name(), self.address()),
+        }
+    }
+}
@@ -1,5 +1,11 @@
+mod chip8;
 mod cpu;
+mod cpu_fault;
 mod fontset;
+mod vip_timing;
 
+pub use self::chip8::Chip8;
 pub use self::cpu::Cpu;
+pub use self::cpu_fault::CpuFault;
 pub use self::fontset::*;
+pub use self::vip_timing::{vip_cycles, VIP_CYCLE_BUDGET, VIP_DISPLAY_CYCLES, VIP_FRAME_CYCLES};
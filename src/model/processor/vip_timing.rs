@@ -0,0 +1,72 @@
+use crate::defines::memory_constants::{PROGRAM_STEP, VARIABLES_COUNT};
+use crate::model::Memory;
+
+// The VIP runs its 1802 at 1.7609 MHz with 8 clock cycles per machine cycle,
+// which gives 3668 machine cycles per 60 Hz frame. The display interrupt keeps
+// the CPU busy for the 128 visible lines of 14 machine cycles each and for its
+// own entry and exit, the rest is left for the interpreter.
+pub const VIP_FRAME_CYCLES: u64 = 3668;
+pub const VIP_DISPLAY_CYCLES: u64 = 1832;
+pub const VIP_CYCLE_BUDGET: u64 = VIP_FRAME_CYCLES - VIP_DISPLAY_CYCLES;
+
+const FETCH_CYCLES: u64 = 40;
+const SKIP_CYCLES: u64 = 4;
+const DEFAULT_CYCLES: u64 = 10;
+
+// Machine cycles the VIP interpreter needs for the instruction that was just
+// executed, including fetching and decoding it. The memory holds the state
+// after the instruction, so taken skips are seen in the program counter, while
+// the registers are the ones the instruction read, before DXYN set VF.
+pub fn vip_cycles(memory: &Memory, registers: &[u8; VARIABLES_COUNT]) -> u64 {
+    let opcode = memory.opcode;
+    let x = ((opcode & 0x0F00) >> 8) as usize;
+    let n = (opcode & 0x000F) as u64;
+    let skipped = memory.program_counter > memory.instruction_address + PROGRAM_STEP;
+    let skip = |cycles: u64| {
+        if skipped {
+            cycles + SKIP_CYCLES
+        } else {
+            cycles
+        }
+    };
+
+    let cycles = match opcode & 0xF000 {
+        0x0000 => match opcode {
+            0x00E0 => 3078,
+            0x00EE => 10,
+            _ => DEFAULT_CYCLES,
+        },
+        0x1000 => 12,
+        0x2000 => 26,
+        0x3000 | 0x4000 => skip(10),
+        0x5000 | 0x9000 => skip(14),
+        0x6000 => 6,
+        0x7000 => 10,
+        0x8000 => 44,
+        0xA000 => 12,
+        0xB000 => 22,
+        0xC000 => 36,
+        0xD000 => {
+            // Sprites that are not byte aligned are shifted into two bytes per row
+            let row_cycles = if registers[x].is_multiple_of(8) {
+                16
+            } else {
+                30
+            };
+            26 + n * row_cycles
+        }
+        0xE000 => skip(14),
+        _ => match opcode & 0x00FF {
+            0x07 | 0x0A | 0x15 | 0x18 => 10,
+            0x1E | 0x29 => 16,
+            0x33 => {
+                let value = registers[x] as u64;
+                80 + (value / 100 + value / 10 % 10 + value % 10) * 16
+            }
+            0x55 | 0x65 => 14 + (x as u64 + 1) * 14,
+            _ => DEFAULT_CYCLES,
+        },
+    };
+
+    FETCH_CYCLES + cycles
+}
@@ -0,0 +1,433 @@
+use crate::controller::{DebugManager, Disassembler, StateManager, PROFILE_REPORT_PATH};
+use crate::defines::{
+    memory_constants::{PROGRAM_STEP, VARIABLES_COUNT},
+    DebugState, GameState, ProgramState, Register,
+};
+use crate::model::{
+    parse_number, parse_range, BreakpointSpec, DebugPropertiesAccess, MemoryAccess, SymbolTable,
+    Watchpoint,
+};
+
+use std::{
+    io,
+    io::{BufRead, Write},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+const DEFAULT_DISASSEMBLY_LINES: usize = 10;
+const MEMORY_LINE_SIZE: usize = 16;
+
+const HELP: &str = "Commands:
+  break [<address> [if <condition>] [ignore <count>]]  set or list breakpoints
+  watch <start>[-<end>] [r|w|rw]                       set a watchpoint
+  delete <address> | delete w<index>                   remove a breakpoint or watchpoint
+  step | next | finish                                 step, step over a call, step out
+  continue | stop                                      run or pause the program
+  regs                                                 show the registers
+  mem <address> <length>                               show memory
+  set <V0-VF|I|PC|SP|DT|ST|S0-SF|[address]> <value>   change a register, stack entry or memory byte
+  disasm [<address>] [<count>]                         disassemble memory
+  bt                                                   show the call stack
+  trace on|off | trace range <start>[-<end>]|off       write an instruction trace
+  profile on|off|reset | profile report [<file>]       count executions and write a report
+  symbols [<file>]                                     list the symbols or load a symbol file
+Addresses can also be given as symbol names";
+
+pub struct DebugConsole {
+    debug_manager: Arc<Mutex<DebugManager>>,
+    state_manager: Arc<Mutex<StateManager>>,
+    memory_access: Arc<Mutex<MemoryAccess>>,
+    debug_properties_access: Arc<Mutex<DebugPropertiesAccess>>,
+}
+
+impl DebugConsole {
+    pub fn new(
+        new_debug_manager: Arc<Mutex<DebugManager>>,
+        new_state_manager: Arc<Mutex<StateManager>>,
+        new_memory_access: Arc<Mutex<MemoryAccess>>,
+        new_debug_properties_access: Arc<Mutex<DebugPropertiesAccess>>,
+    ) -> DebugConsole {
+        DebugConsole {
+            debug_manager: new_debug_manager,
+            state_manager: new_state_manager,
+            memory_access: new_memory_access,
+            debug_properties_access: new_debug_properties_access,
+        }
+    }
+
+    pub fn start(mut self) {
+        thread::Builder::new()
+            .name("Console".to_string())
+            .spawn(move || self.run())
+            .unwrap();
+    }
+
+    fn run(&mut self) {
+        println!("Debug console, type 'help' for a list of commands");
+        DebugConsole::prompt();
+
+        let stdin = io::stdin();
+        for line in stdin.lock().lines() {
+            match line {
+                Ok(line) => {
+                    if let Err(error) = self.execute(line.trim()) {
+                        println!("{}", error);
+                    }
+                }
+                Err(_) => break,
+            }
+            DebugConsole::prompt();
+        }
+    }
+
+    fn prompt() {
+        print!("(chip8) ");
+        io::stdout().flush().unwrap_or(());
+    }
+
+    pub fn execute(&mut self, line: &str) -> Result<(), String> {
+        let mut parts = line.splitn(2, char::is_whitespace);
+        let command = parts.next().unwrap_or("");
+        let arguments = parts.next().unwrap_or("").trim();
+
+        match command {
+            "" => {}
+            "help" => println!("{}", HELP),
+            "break" | "b" if arguments.is_empty() => self.list_breakpoints(),
+            "break" | "b" => {
+                let spec = BreakpointSpec::parse(arguments, &self.symbols())?;
+                self.debug_manager.lock().unwrap().set_breakpoint(&spec)?;
+                println!("Breakpoint at {:04X}", spec.address);
+            }
+            "watch" => {
+                let watchpoint = Watchpoint::parse(arguments, &self.symbols())?;
+                self.debug_manager
+                    .lock()
+                    .unwrap()
+                    .set_watchpoint(watchpoint);
+                println!(
+                    "Watchpoint on {:04X}-{:04X} {}",
+                    watchpoint.start,
+                    watchpoint.end,
+                    watchpoint.kind.name()
+                );
+            }
+            "delete" | "d" => self.delete(arguments)?,
+            "step" | "s" => {
+                self.debug_manager.lock().unwrap().pause();
+                self.debug_manager.lock().unwrap().step_trough();
+                self.wait_for_stop();
+            }
+            "next" | "n" => {
+                self.debug_manager.lock().unwrap().pause();
+                self.debug_manager.lock().unwrap().step_over();
+                self.wait_for_stop();
+            }
+            "finish" => {
+                self.debug_manager.lock().unwrap().pause();
+                self.debug_manager.lock().unwrap().step_out();
+                self.wait_for_stop();
+            }
+            "continue" | "c" => self.debug_manager.lock().unwrap().resume(),
+            "stop" => {
+                self.debug_manager.lock().unwrap().pause();
+                self.print_location();
+            }
+            "regs" | "r" => self.print_registers(),
+            "mem" | "x" => self.print_memory(arguments)?,
+            "set" => self.set_value(arguments)?,
+            "disasm" => self.print_disassembly(arguments)?,
+            "bt" => self.print_backtrace(),
+            "trace" => self.trace(arguments)?,
+            "profile" => self.profile(arguments)?,
+            "symbols" => self.symbols_command(arguments)?,
+            _ => return Err(format!("Error: Unknown command: {}", command)),
+        }
+
+        Ok(())
+    }
+
+    fn wait_for_stop(&mut self) {
+        loop {
+            let (program_state, game_state) = {
+                let mut state_manager = self.state_manager.lock().unwrap();
+                (state_manager.get_state(), state_manager.get_game_state())
+            };
+            if program_state != ProgramState::Debug(DebugState::Step)
+                && game_state != GameState::Running
+            {
+                break;
+            }
+            thread::sleep(Duration::from_millis(2));
+        }
+
+        self.print_location();
+    }
+
+    fn print_location(&mut self) {
+        let program_counter = self.memory_access.lock().unwrap().get_program_counter();
+        println!("{}", self.disassemble_line(program_counter));
+    }
+
+    fn list_breakpoints(&mut self) {
+        let mut properties = self.debug_properties_access.lock().unwrap();
+        for (address, breakpoint) in properties.get_breakpoints().iter() {
+            let mut line = format!("{:04X}  hits {}", address, breakpoint.hit_count);
+            if breakpoint.ignore_count > 0 {
+                line.push_str(&format!("  ignore {}", breakpoint.ignore_count));
+            }
+            if breakpoint.condition.is_some() {
+                line.push_str(&format!("  if {}", breakpoint.condition_text));
+            }
+            println!("{}", line);
+        }
+        for (index, watchpoint) in properties.get_watchpoints().iter().enumerate() {
+            println!(
+                "w{}  {:04X}-{:04X} {}  hits {}",
+                index,
+                watchpoint.start,
+                watchpoint.end,
+                watchpoint.kind.name(),
+                watchpoint.hit_count
+            );
+        }
+    }
+
+    fn delete(&mut self, arguments: &str) -> Result<(), String> {
+        let symbols = self.symbols();
+        let mut debug_manager = self.debug_manager.lock().unwrap();
+        let watchpoint = arguments
+            .strip_prefix('w')
+            .and_then(|index| index.parse::<usize>().ok());
+        let removed = if let Some(index) = watchpoint {
+            debug_manager.remove_watchpoint(index)
+        } else {
+            let address = symbols
+                .resolve(arguments)
+                .ok_or(format!("Error: Invalid address: {}", arguments))?;
+            debug_manager.remove_breakpoint(address)
+        };
+
+        if !removed {
+            return Err(format!("Error: Nothing to delete at {}", arguments));
+        }
+
+        Ok(())
+    }
+
+    fn trace(&mut self, arguments: &str) -> Result<(), String> {
+        let mut parts = arguments.split_whitespace();
+        let mut debug_manager = self.debug_manager.lock().unwrap();
+        match (parts.next(), parts.next()) {
+            (Some("on"), None) => debug_manager.set_trace(true)?,
+            (Some("off"), None) => debug_manager.set_trace(false)?,
+            (Some("range"), Some("off")) => debug_manager.set_trace_range(None),
+            (Some("range"), Some(range)) => debug_manager.set_trace_range(Some(
+                parse_range(range).ok_or(format!("Error: Invalid trace range: {}", range))?,
+            )),
+            _ => {
+                return Err("Error: Usage: trace on|off|range <start>[-<end>]|range off".to_string())
+            }
+        }
+
+        Ok(())
+    }
+
+    fn profile(&mut self, arguments: &str) -> Result<(), String> {
+        let mut parts = arguments.split_whitespace();
+        let mut debug_manager = self.debug_manager.lock().unwrap();
+        match (parts.next(), parts.next()) {
+            (Some("on"), None) => debug_manager.set_profiling(true),
+            (Some("off"), None) => debug_manager.set_profiling(false),
+            (Some("reset"), None) => debug_manager.reset_profile(),
+            (Some("report"), path) => {
+                let path = path.unwrap_or(PROFILE_REPORT_PATH);
+                debug_manager.export_profile(path)?;
+                println!("Wrote profile to {}", path);
+            }
+            _ => return Err("Error: Usage: profile on|off|reset|report [<file>]".to_string()),
+        }
+
+        Ok(())
+    }
+
+    fn symbols_command(&mut self, arguments: &str) -> Result<(), String> {
+        if !arguments.is_empty() {
+            let count = self.debug_manager.lock().unwrap().load_symbols(arguments)?;
+            println!("Loaded {} symbols from {}", count, arguments);
+            return Ok(());
+        }
+
+        for (address, name) in self.symbols().iter() {
+            println!("{:04X}  {}", address, name);
+        }
+
+        Ok(())
+    }
+
+    fn symbols(&mut self) -> Arc<SymbolTable> {
+        self.debug_properties_access.lock().unwrap().get_symbols()
+    }
+
+    fn print_registers(&mut self) {
+        let mut access = self.memory_access.lock().unwrap();
+        let registers = access.get_variable_register();
+        for row in registers.chunks(VARIABLES_COUNT / 2).enumerate() {
+            let line: Vec<String> = row
+                .1
+                .iter()
+                .enumerate()
+                .map(|(index, value)| {
+                    format!("V{:X}={:02X}", row.0 * VARIABLES_COUNT / 2 + index, value)
+                })
+                .collect();
+            println!("{}", line.join(" "));
+        }
+        println!(
+            "I={:04X} PC={:04X} SP={:X} DT={:02X} ST={:02X}",
+            access.get_index_register(),
+            access.get_program_counter(),
+            access.get_stack_pointer(),
+            access.get_delay_timer(),
+            access.get_sound_timer()
+        );
+    }
+
+    fn print_memory(&mut self, arguments: &str) -> Result<(), String> {
+        let mut parts = arguments.split_whitespace();
+        let address = parts
+            .next()
+            .ok_or("Error: Usage: mem <address> <length>".to_string())?;
+        let address = self
+            .symbols()
+            .resolve(address)
+            .ok_or(format!("Error: Invalid address: {}", address))?;
+        let length = parts
+            .next()
+            .and_then(parse_number)
+            .unwrap_or(MEMORY_LINE_SIZE);
+        let bytes = self
+            .memory_access
+            .lock()
+            .unwrap()
+            .get_memory_range(address, length)
+            .ok_or(format!("Error: Address out of range: {:04X}", address))?;
+
+        for (row, chunk) in bytes.chunks(MEMORY_LINE_SIZE).enumerate() {
+            let values: Vec<String> = chunk.iter().map(|byte| format!("{:02X}", byte)).collect();
+            println!(
+                "{:04X}: {}",
+                address + row * MEMORY_LINE_SIZE,
+                values.join(" ")
+            );
+        }
+
+        Ok(())
+    }
+
+    fn set_value(&mut self, arguments: &str) -> Result<(), String> {
+        let mut parts = arguments.split_whitespace();
+        let target = parts
+            .next()
+            .ok_or("Error: Usage: set <target> <value>".to_string())?;
+        let value = parts
+            .next()
+            .and_then(parse_number)
+            .ok_or("Error: Usage: set <target> <value>".to_string())?;
+        let symbols = self.symbols();
+        let mut access = self.memory_access.lock().unwrap();
+
+        let memory_target = target
+            .strip_prefix('[')
+            .and_then(|target| target.strip_suffix(']'));
+        let success = if let Some(address) = memory_target {
+            let address = symbols
+                .resolve(address)
+                .ok_or(format!("Error: Invalid address: {}", target))?;
+            value <= 0xFF && access.set_memory_at(address, value as u8)
+        } else {
+            let register =
+                Register::from_name(target).ok_or(format!("Error: Unknown target: {}", target))?;
+            access.set_register(register, value)
+        };
+
+        if !success {
+            return Err(format!("Error: Invalid value for {}: {:X}", target, value));
+        }
+
+        Ok(())
+    }
+
+    fn print_disassembly(&mut self, arguments: &str) -> Result<(), String> {
+        let mut parts = arguments.split_whitespace();
+        let address = match parts.next() {
+            Some(address) => self
+                .symbols()
+                .resolve(address)
+                .ok_or(format!("Error: Invalid address: {}", address))?,
+            None => self.memory_access.lock().unwrap().get_program_counter(),
+        };
+        let count = parts
+            .next()
+            .and_then(parse_number)
+            .unwrap_or(DEFAULT_DISASSEMBLY_LINES);
+
+        for line in 0..count {
+            println!("{}", self.disassemble_line(address + line * PROGRAM_STEP));
+        }
+
+        Ok(())
+    }
+
+    fn print_backtrace(&mut self) {
+        let (frames, program_counter, fault) = {
+            let mut access = self.memory_access.lock().unwrap();
+            (
+                access.get_call_frames(),
+                access.get_program_counter(),
+                access.get_fault(),
+            )
+        };
+
+        if let Some(fault) = fault {
+            println!("Error: {}", fault);
+        }
+        println!("#0  {:04X}", program_counter);
+        for (frame, (call_site, return_address)) in frames.iter().enumerate() {
+            println!(
+                "#{}  {}  returns to {:04X}",
+                frame + 1,
+                self.disassemble_line(*call_site),
+                return_address
+            );
+        }
+    }
+
+    fn disassemble_line(&mut self, address: usize) -> String {
+        let symbols = self.symbols();
+        let line = match self
+            .memory_access
+            .lock()
+            .unwrap()
+            .get_memory_range(address, 2)
+        {
+            Some(bytes) => {
+                let opcode = (bytes[0] as u16) << 8 | bytes[1] as u16;
+                format!(
+                    "{:04X}: {:04X}  {}",
+                    address,
+                    opcode,
+                    Disassembler::disassemble_with_symbols(&opcode, &symbols)
+                )
+            }
+            None => format!("{:04X}: ----", address),
+        };
+
+        match symbols.name(address) {
+            Some(name) => format!("{} <{}>", line, name),
+            None => line,
+        }
+    }
+}
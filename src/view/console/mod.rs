@@ -0,0 +1,3 @@
+mod debug_console;
+
+pub use self::debug_console::DebugConsole;
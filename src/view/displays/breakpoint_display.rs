@@ -2,10 +2,10 @@ use crate::defines::{
     layout_constants::{
         BEAKPOINT_START_X, BREAKPOINT_HEIGHT, BREAKPOINT_START_Y, BREAKPOINT_WIDTH,
     },
-    memory_constants::VARIABLES_COUNT,
+    memory_constants::{PROGRAM_START, VARIABLES_COUNT},
     Fill, IDisplay,
 };
-use crate::model::DebugPropertiesAccess;
+use crate::model::{DebugPropertiesAccess, MemoryAccess};
 use crate::view::DisplayRenderHelper;
 use std::{
     result::Result,
@@ -22,6 +22,7 @@ use sdl2::{render::WindowCanvas, ttf::Sdl2TtfContext};
 pub struct BreakPointDisplay {
     breakpoints: Vec<String>,
     program_manager: Arc<Mutex<DebugPropertiesAccess>>,
+    memory_access: Arc<Mutex<MemoryAccess>>,
     render_helper: DisplayRenderHelper,
 }
 
@@ -29,16 +30,63 @@ impl IDisplay for BreakPointDisplay {
     fn update_info(&mut self) {
         let mut properties = self.program_manager.lock().unwrap();
         let breakpoint_map = properties.get_breakpoints();
-        let mut index: usize = 0;
+        let symbols = properties.get_symbols();
+        let mut memory_access = self.memory_access.lock().unwrap();
+        let mut lines: Vec<String> = Vec::new();
 
-        for (line, opcode) in breakpoint_map.iter() {
-            if index < VARIABLES_COUNT - 1 {
-                self.breakpoints[index] = format!("Line {:03X}: 0x{:04X}", line, opcode);
+        for (address, breakpoint) in breakpoint_map.iter() {
+            let opcode = address
+                .checked_sub(PROGRAM_START)
+                .and_then(|line| memory_access.get_opcode_at(line))
+                .unwrap_or(0);
+            let mut line = format!("{:04X}: {:04X} #{}", address, opcode, breakpoint.hit_count);
+            if breakpoint.ignore_count > 0 {
+                line.push_str(&format!("/{}", breakpoint.ignore_count));
             }
-            index += 1;
+            lines.push(line);
+            if let Some(name) = symbols.name(*address) {
+                lines.push(format!(" {}", name));
+            }
+            if breakpoint.condition.is_some() {
+                lines.push(format!(
+                    " if {}",
+                    breakpoint.condition_text.replace(' ', "")
+                ));
+            }
+        }
+
+        for watchpoint in properties.get_watchpoints().iter() {
+            lines.push(format!(
+                "W{:04X}-{:04X} {} #{}",
+                watchpoint.start,
+                watchpoint.end,
+                watchpoint.kind.name(),
+                watchpoint.hit_count
+            ));
+            if let Some(name) = symbols.name(watchpoint.start) {
+                lines.push(format!(" {}", name));
+            }
+        }
+        if let Some(hit) = properties.get_last_watch_hit() {
+            lines.push(format!(
+                "{:04X}@{:04X} {:02X}>{:02X}",
+                hit.event.address, hit.program_counter, hit.event.old_value, hit.event.new_value
+            ));
+        }
+
+        let capacity = self.breakpoints.len();
+        if lines.len() > capacity {
+            let hidden = lines.len() - (capacity - 1);
+            lines.truncate(capacity - 1);
+            lines.push(format!("+{} more", hidden));
+        }
+
+        let count = lines.len();
+        for (entry, line) in self.breakpoints.iter_mut().zip(lines) {
+            *entry = line;
         }
 
-        self.breakpoints.fill_to_end(index);
+        self.breakpoints.fill_to_end(count);
     }
 
     fn redraw(
@@ -57,10 +105,14 @@ impl IDisplay for BreakPointDisplay {
 }
 
 impl BreakPointDisplay {
-    pub fn new(new_debug_properties: Arc<Mutex<DebugPropertiesAccess>>) -> BreakPointDisplay {
+    pub fn new(
+        new_debug_properties: Arc<Mutex<DebugPropertiesAccess>>,
+        new_memory_access: Arc<Mutex<MemoryAccess>>,
+    ) -> BreakPointDisplay {
         BreakPointDisplay {
             breakpoints: vec![String::with_capacity(6); VARIABLES_COUNT - 1],
             program_manager: new_debug_properties,
+            memory_access: new_memory_access,
             render_helper: DisplayRenderHelper::new(
                 BEAKPOINT_START_X,
                 BREAKPOINT_START_Y,
@@ -1,13 +1,11 @@
 use crate::defines::{
-    layout_constants::{
-        GAME_PIXEL_SET, GAME_PIXEL_TEST, GAME_PIXEL_UNSET, GAME_PIXEL_UNTEST, GAME_START_X,
-        GAME_START_Y, PIXEL_SCALE,
-    },
-    memory_constants::{COLUMNS, GRAPHIC_SIZE, ROWS},
+    game_constants::PALETTE_SIZE,
+    layout_constants::{GAME_START_X, GAME_START_Y, PIXEL_SCALE},
+    memory_constants::{COLUMNS, ROWS},
     IDisplay,
 };
-use crate::model::{MemoryAccess, Resolution};
-use sdl2::{rect, render::WindowCanvas, ttf::Sdl2TtfContext};
+use crate::model::{GamePropertiesAccess, MemoryAccess, Resolution};
+use sdl2::{pixels::Color, rect, render::WindowCanvas, ttf::Sdl2TtfContext};
 use std::{
     result::Result,
     sync::{Arc, Mutex},
@@ -15,21 +13,38 @@ use std::{
 
 pub struct GameDisplay {
     memory_access: Arc<Mutex<MemoryAccess>>,
+    game_properties_access: Arc<Mutex<GamePropertiesAccess>>,
     pixel_state: Vec<u8>,
     resolution: Resolution,
     pixel_scale: usize,
+    palette: [Color; PALETTE_SIZE],
 }
 
 impl GameDisplay {
-    pub fn new(mem_access: Arc<Mutex<MemoryAccess>>) -> GameDisplay {
+    pub fn new(
+        mem_access: Arc<Mutex<MemoryAccess>>,
+        new_game_properties_access: Arc<Mutex<GamePropertiesAccess>>,
+    ) -> GameDisplay {
         let array = mem_access.lock().unwrap().get_graphic_array();
+        let palette = new_game_properties_access.lock().unwrap().get_palette();
         GameDisplay {
             memory_access: mem_access,
+            game_properties_access: new_game_properties_access,
             pixel_state: array,
             resolution: Resolution::Low,
             pixel_scale: PIXEL_SCALE,
+            palette: GameDisplay::convert_palette(palette),
         }
     }
+
+    fn convert_palette(palette: [u32; PALETTE_SIZE]) -> [Color; PALETTE_SIZE] {
+        let mut colors = [Color::BLACK; PALETTE_SIZE];
+        for (color, rgb) in colors.iter_mut().zip(palette.iter()) {
+            *color = Color::RGB((rgb >> 16) as u8, (rgb >> 8) as u8, *rgb as u8);
+        }
+
+        colors
+    }
 }
 
 impl IDisplay for GameDisplay {
@@ -38,6 +53,8 @@ impl IDisplay for GameDisplay {
         self.pixel_state = access.get_graphic_array();
         self.resolution = access.get_resolution();
         self.pixel_scale = PIXEL_SCALE / self.resolution as usize;
+        self.palette =
+            GameDisplay::convert_palette(self.game_properties_access.lock().unwrap().get_palette());
     }
 
     fn redraw(
@@ -58,11 +75,8 @@ impl IDisplay for GameDisplay {
         for y in 0..rows {
             rect.set_y((y * self.pixel_scale) as i32 + GAME_START_Y);
             for x in 0..columns {
-                if self.pixel_state[(y * columns) + x] == 1 {
-                    canvas.set_draw_color(GAME_PIXEL_TEST);
-                } else {
-                    canvas.set_draw_color(GAME_PIXEL_UNSET);
-                }
+                let pixel = self.pixel_state[(y * columns) + x] as usize;
+                canvas.set_draw_color(self.palette[pixel % PALETTE_SIZE]);
                 rect.set_x((x * self.pixel_scale) as i32 + GAME_START_X);
                 canvas.fill_rect(rect)?;
             }
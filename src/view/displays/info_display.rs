@@ -1,8 +1,10 @@
 use crate::defines::{
     layout_constants::{INFO_HEIGHT, INFO_START_X, INFO_START_Y, INFO_WIDTH},
-    DebugState, GameState, IDisplay,
+    DebugState, GameState, IDisplay, TimingMode,
+};
+use crate::model::{
+    CpuFault, DebugPropertiesAccess, GamePropertiesAccess, MemoryAccess, StatesAccess,
 };
-use crate::model::{GamePropertiesAccess, StatesAccess};
 use crate::view::DisplayRenderHelper;
 use std::{
     cell::RefCell,
@@ -17,15 +19,38 @@ use std::{
 };
 
 use sdl2::{render::WindowCanvas, ttf::Sdl2TtfContext};
-//Controls:
-// F5: contninue / stop
-// F6: step
-// +/-: speed
-// F7: breakpoint
-// F8: (maybe) step into
-// F9:: Memory dump
-// F1: restart
-// F3: Open program in Editor
+
+const CONTROLS_START: usize = 9;
+const DEBUG_CONTROL: &str = "F3 : Enable Debug";
+const CONTROL_PAGES: &[&[&str]] = &[
+    &[
+        "F1 : Reset",
+        DEBUG_CONTROL,
+        "F4 : Dump Memory",
+        "F5 : Stop/Continue",
+        "F6 : Step",
+        "F7 : breakpoint",
+        "+/-: Speed",
+    ],
+    &[
+        "F8 : Step Back",
+        "F9 : Step Over",
+        "F10: Step Out",
+        "F11: Run to Cursor",
+        "RMB: Set Cursor",
+        "F12: Toggle Trace",
+        "T  : Timing Mode",
+    ],
+    &[
+        "P  : Quirk Profile",
+        "L  : Open File",
+        "Ctrl+0-9: Save State",
+        "Shift+0-9: Load State",
+        "Backspace: Rewind",
+        "H  : Profiler/Heatmap",
+        "J  : Write Profile",
+    ],
+];
 
 pub struct InfoDisplay {
     game_name: String,
@@ -35,6 +60,9 @@ pub struct InfoDisplay {
     game_properties_access: Arc<Mutex<GamePropertiesAccess>>,
     debug_state: DebugState,
     states_access: Arc<Mutex<StatesAccess>>,
+    debug_properties_access: Arc<Mutex<DebugPropertiesAccess>>,
+    memory_access: Arc<Mutex<MemoryAccess>>,
+    fault: Option<CpuFault>,
     render_helper: DisplayRenderHelper,
 }
 
@@ -47,12 +75,27 @@ impl IDisplay for InfoDisplay {
 
         self.controls[3] = format!("Game: {}", self.game_name.as_str());
         self.controls[4] = format!("Size: {} Bytes", self.game_size);
+        self.fault = self.memory_access.lock().unwrap().get_fault();
+        let draw_calls = self
+            .debug_properties_access
+            .lock()
+            .unwrap()
+            .get_draw_calls();
+        self.controls[2] = match (self.fault, draw_calls) {
+            (Some(fault), _) => format!("{} @{:04X}", fault.name(), fault.address()),
+            (None, Some(draw_calls)) => format!(
+                "Draws: {}/frame max {}",
+                draw_calls.last_frame, draw_calls.max_frame
+            ),
+            (None, None) => " ".to_string(),
+        };
 
         let mut state = String::new();
         self.game_state = states_access.get_game_state();
         self.debug_state = states_access.get_debug_state();
 
         match self.game_state {
+            _ if self.fault.is_some() => state = "Fault".to_string(),
             GameState::Running => state = "Running".to_string(),
             GameState::Stopped => state = "Stopped".to_string(),
             GameState::Failed => state = "Finished".into(),
@@ -65,8 +108,25 @@ impl IDisplay for InfoDisplay {
             _ => state = "Disable Debug".to_string(),
         }
 
-        self.controls[10] = format!("F3 : {}", state);
-        self.controls[6] = format!("Speed: {}", properties_access.get_game_speed());
+        let page = properties_access.get_control_page() % CONTROL_PAGES.len();
+        self.controls[8] = format!("Controls {}/{} (Tab)", page + 1, CONTROL_PAGES.len());
+        for line in CONTROLS_START..self.controls.len() {
+            self.controls[line] = match CONTROL_PAGES[page].get(line - CONTROLS_START) {
+                Some(&DEBUG_CONTROL) => format!("F3 : {}", state),
+                Some(control) => control.to_string(),
+                None => String::new(),
+            };
+        }
+        let (timer_rate, instruction_rate) = properties_access.get_rates();
+        let speed = match properties_access.get_timing_mode() {
+            TimingMode::Flat => format!("{} IPF", properties_access.get_game_speed()),
+            TimingMode::Vip => TimingMode::Vip.name().to_string(),
+        };
+        self.controls[6] = format!(
+            "Speed: {}, {:.0} IPS, {:.1} Hz",
+            speed, instruction_rate, timer_rate
+        );
+        self.controls[7] = format!("Quirks: {}", properties_access.get_quirk_profile().name());
     }
 
     fn redraw(
@@ -85,6 +145,8 @@ impl InfoDisplay {
     pub fn new(
         new_program_manager: Arc<Mutex<GamePropertiesAccess>>,
         new_states_access: Arc<Mutex<StatesAccess>>,
+        new_debug_properties_access: Arc<Mutex<DebugPropertiesAccess>>,
+        new_memory_access: Arc<Mutex<MemoryAccess>>,
     ) -> InfoDisplay {
         let mut display_text: Vec<String> = vec![String::new(); 16];
         display_text[0] = "Chip 8  Emulator".to_string();
@@ -94,15 +156,8 @@ impl InfoDisplay {
         display_text[4] = "Size: ".to_string();
         display_text[5] = "Status: ".to_string();
         display_text[6] = "Speed: ".to_string();
-        display_text[7] = " ".to_string();
+        display_text[7] = "Quirks: ".to_string();
         display_text[8] = "Controls".to_string();
-        display_text[9] = "F1 : Reset".to_string();
-        display_text[10] = "F3 : Enable Debug".to_string();
-        display_text[11] = "F4 : Dump Memory".to_string();
-        display_text[12] = "F5 : Stop/Continue".to_string();
-        display_text[13] = "F6 : Step".to_string();
-        display_text[14] = "F7 : breakpoint".to_string();
-        display_text[15] = "+/-: Speed".to_string();
 
         InfoDisplay {
             game_name: String::new(),
@@ -110,6 +165,9 @@ impl InfoDisplay {
             game_size: 0,
             game_properties_access: new_program_manager,
             states_access: new_states_access,
+            debug_properties_access: new_debug_properties_access,
+            memory_access: new_memory_access,
+            fault: None,
             game_state: GameState::Running,
             debug_state: DebugState::Disabled,
             render_helper: DisplayRenderHelper::new(
@@ -1,9 +1,12 @@
 use crate::defines::{
-    layout_constants::{MEMORY_HEIGHT, MEMORY_START_X, MEMORY_START_Y, MEMORY_WIDTH},
+    layout_constants::{
+        MEMORY_HEIGHT, MEMORY_REGISTER_ROWS, MEMORY_START_X, MEMORY_START_Y, MEMORY_WIDTH,
+        REGISTER_EDIT,
+    },
     memory_constants::{FLAG_REGISTER_SIZE, VARIABLES_COUNT},
-    IDisplay,
+    IDisplay, Register,
 };
-use crate::model::MemoryAccess;
+use crate::model::{DebugPropertiesAccess, MemoryAccess};
 use crate::view::DisplayRenderHelper;
 use std::{
     cell::RefCell,
@@ -24,6 +27,8 @@ pub struct MemoryDisplay {
     flag_register: Vec<String>,
     remaining_register: Vec<String>,
     memory_access: Arc<Mutex<MemoryAccess>>,
+    debug_properties_access: Arc<Mutex<DebugPropertiesAccess>>,
+    edit_position: Option<(usize, usize)>,
     render_helper: DisplayRenderHelper,
 }
 
@@ -59,6 +64,32 @@ impl IDisplay for MemoryDisplay {
                 variables[register_index - i]
             );
         }
+
+        self.edit_position = None;
+        if let Some(edit) = self
+            .debug_properties_access
+            .lock()
+            .unwrap()
+            .get_register_edit()
+        {
+            let text = format!("{}: {}_", edit.register.name(), edit.input);
+            match edit.register {
+                Register::Variable(index) => {
+                    let row = VARIABLES_COUNT - 1 - index;
+                    self.variable_register[row] = text;
+                    self.edit_position = Some((0, row));
+                }
+                register => {
+                    if let Some(row) = MEMORY_REGISTER_ROWS
+                        .iter()
+                        .position(|entry| *entry == Some(register))
+                    {
+                        self.remaining_register[row] = text;
+                        self.edit_position = Some((1, row));
+                    }
+                }
+            }
+        }
     }
 
     fn redraw(
@@ -74,17 +105,32 @@ impl IDisplay for MemoryDisplay {
         self.render_helper
             .draw_lines_with_x(&mut right_side, canvas, ttf_context, start_x)?;
 
+        if let Some((column, row)) = self.edit_position {
+            let mut highlight_helper = DisplayRenderHelper::new(
+                MEMORY_START_X + (column * MEMORY_WIDTH as usize / 2) as i32,
+                MEMORY_START_Y,
+                MEMORY_WIDTH / 2,
+                MEMORY_HEIGHT,
+            );
+            highlight_helper.draw_rectangle(canvas, row as i32, REGISTER_EDIT)?;
+        }
+
         Ok(())
     }
 }
 
 impl MemoryDisplay {
-    pub fn new(new_memory_access: Arc<Mutex<MemoryAccess>>) -> MemoryDisplay {
+    pub fn new(
+        new_memory_access: Arc<Mutex<MemoryAccess>>,
+        new_debug_properties_access: Arc<Mutex<DebugPropertiesAccess>>,
+    ) -> MemoryDisplay {
         MemoryDisplay {
             variable_register: vec![String::with_capacity(6); VARIABLES_COUNT],
             remaining_register: vec![String::with_capacity(6); 8],
             flag_register: vec![String::with_capacity(6); FLAG_REGISTER_SIZE],
             memory_access: new_memory_access,
+            debug_properties_access: new_debug_properties_access,
+            edit_position: None,
             render_helper: DisplayRenderHelper::new(
                 MEMORY_START_X,
                 MEMORY_START_Y,
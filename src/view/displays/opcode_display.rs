@@ -1,13 +1,17 @@
+use crate::controller::Listing;
 use crate::defines::{
     layout_constants::{
-        OPCODE_HEIGHT, OPCODE_HIGHLIGHT_DEBUG, OPCODE_HIGHLIGHT_TEST, OPCODE_LINES, OPCODE_START_X,
-        OPCODE_START_Y, OPCODE_WIDTH,
+        HEAT_COLD, HEAT_HOT, HEAT_WIDTH, OPCODE_CURSOR, OPCODE_HEIGHT, OPCODE_HIGHLIGHT_DEBUG,
+        OPCODE_HIGHLIGHT_TEST, OPCODE_LINES, OPCODE_SCROLL_CONTEXT, OPCODE_START_X, OPCODE_START_Y,
+        OPCODE_WIDTH,
     },
     memory_constants::PROGRAM_START,
-    DebugState, IDisplay,
+    DebugState, IDisplay, InstructionSet,
 };
-use crate::model::{DebugPropertiesAccess, GamePropertiesAccess, MemoryAccess, StatesAccess};
-use crate::view::{Disassembler, DisplayRenderHelper};
+use crate::model::{
+    DebugPropertiesAccess, GamePropertiesAccess, MemoryAccess, StatesAccess, SymbolTable,
+};
+use crate::view::DisplayRenderHelper;
 use std::{
     collections::HashSet,
     result::Result,
@@ -16,57 +20,102 @@ use std::{
 
 use sdl2::{pixels::Color, render::WindowCanvas, ttf::Sdl2TtfContext};
 
+const LABEL_WIDTH: usize = 10;
+const LISTING_DATA_BYTES: usize = 2;
+
 pub struct OpcodeDisplay {
     code_lines: Vec<String>,
+    code: Vec<u8>,
+    listing: Listing,
+    symbols: Arc<SymbolTable>,
+    entry_points: Vec<usize>,
+    addresses: Vec<usize>,
     memory_access: Arc<Mutex<MemoryAccess>>,
     game_properties_access: Arc<Mutex<GamePropertiesAccess>>,
     debug_properties_access: Arc<Mutex<DebugPropertiesAccess>>,
     states_access: Arc<Mutex<StatesAccess>>,
     offset: usize,
-    current_line: usize,
+    current_line: Option<usize>,
+    scroll_address: Option<usize>,
     render_helper: DisplayRenderHelper,
     breakpoints: HashSet<usize>,
+    cursor: Option<usize>,
+    heat: Option<(Vec<u64>, u64)>,
+    heat_helper: DisplayRenderHelper,
     highlight_color: Color,
 }
 
 impl IDisplay for OpcodeDisplay {
     fn update_info(&mut self) {
-        let mut access = self.memory_access.lock().unwrap();
-        let mut properties = self.game_properties_access.lock().unwrap();
-
-        self.current_line = access.get_program_counter() - PROGRAM_START;
-        let program_size = properties.get_game_size();
+        let program_counter = self.memory_access.lock().unwrap().get_program_counter();
+        let code = self.game_properties_access.lock().unwrap().get_game_code();
+        let symbols = self.debug_properties_access.lock().unwrap().get_symbols();
+        self.update_listing(code, symbols, program_counter);
+        self.current_line = self.listing.line_index(program_counter);
 
         if self.states_access.lock().unwrap().get_debug_state() == DebugState::Enabled {
             self.highlight_color = OPCODE_HIGHLIGHT_DEBUG;
         } else {
             self.highlight_color = OPCODE_HIGHLIGHT_TEST;
         }
-        if let Some(offset_change) = self.update_offset(program_size) {
+        let scroll = self
+            .debug_properties_access
+            .lock()
+            .unwrap()
+            .take_opcode_scroll();
+        let offset_change = match scroll.and_then(|address| self.listing.line_index(address)) {
+            Some(line) => {
+                self.scroll_address = Some(program_counter);
+                Some(line.saturating_sub(OPCODE_SCROLL_CONTEXT))
+            }
+            None if self.scroll_address == Some(program_counter) => None,
+            None => {
+                self.scroll_address = None;
+                self.update_offset()
+            }
+        };
+        if let Some(offset_change) = offset_change {
             self.offset = offset_change;
-            let start = self.offset;
-            if let Some(code_snippet) = access.get_code_snippet(OPCODE_LINES / 2, self.offset) {
-                let disassambled_code = Disassembler::disassemble_list(&code_snippet);
+        }
 
-                for (i, iter) in self.code_lines.iter_mut().enumerate() {
-                    *iter = format!("{:004} - ", start + i * 2);
-                    iter.push_str(&disassambled_code[i]);
+        let mut lines = self.listing.lines.iter().skip(self.offset);
+        self.addresses.clear();
+        for iter in self.code_lines.iter_mut() {
+            *iter = match lines.next() {
+                Some(line) => {
+                    self.addresses.push(line.address);
+                    let label: String = self
+                        .listing
+                        .label(line.address)
+                        .unwrap_or("")
+                        .chars()
+                        .take(LABEL_WIDTH)
+                        .collect();
+                    format!(
+                        "{:04X} {:<width$} {}",
+                        line.address,
+                        label,
+                        self.listing.text(line),
+                        width = LABEL_WIDTH
+                    )
                 }
-            } else {
-                for (_i, iter) in self.code_lines.iter_mut().enumerate() {
-                    *iter = "0000".to_string();
-                }
-            }
+                None => " ".to_string(),
+            };
         }
 
+        let mut debug_properties = self.debug_properties_access.lock().unwrap();
+        debug_properties.set_opcode_addresses(self.addresses.clone());
+        self.cursor = debug_properties
+            .get_cursor()
+            .and_then(|cursor| self.addresses.iter().position(|address| *address == cursor));
+        self.heat = debug_properties.get_heat(&self.addresses);
+        let breakpoints = debug_properties.get_breakpoints();
         self.breakpoints = self
-            .debug_properties_access
-            .lock()
-            .unwrap()
-            .get_breakpoints()
-            .keys()
-            .cloned()
-            .filter(|key| *key >= self.offset && *key < self.offset + OPCODE_LINES)
+            .addresses
+            .iter()
+            .enumerate()
+            .filter(|(_, address)| breakpoints.contains_key(address))
+            .map(|(row, _)| row)
             .collect();
     }
 
@@ -75,21 +124,42 @@ impl IDisplay for OpcodeDisplay {
         canvas: &mut WindowCanvas,
         ttf_context: &mut Sdl2TtfContext,
     ) -> Result<(), String> {
-        let mut rect_y: i32 = (self.current_line - self.offset) as i32 / 2;
-        if rect_y == OPCODE_LINES as i32 {
-            rect_y -= 1;
+        let mut rect_y: i32;
+        if let Some(row) = self
+            .current_line
+            .and_then(|line| line.checked_sub(self.offset))
+            .filter(|row| *row < OPCODE_LINES / 2)
+        {
+            rect_y = row as i32;
+            self.render_helper
+                .fill_rectangle(canvas, rect_y, self.highlight_color)?;
         }
-        self.render_helper
-            .fill_rectangle(canvas, rect_y, self.highlight_color)?;
         self.render_helper
             .draw_lines(&mut self.code_lines, canvas, ttf_context)?;
 
         for iter in self.breakpoints.iter() {
-            rect_y = (*iter - self.offset) as i32 / 2;
+            rect_y = *iter as i32;
             self.render_helper
                 .draw_rectangle(canvas, rect_y, OPCODE_HIGHLIGHT_DEBUG)?;
         }
 
+        if let Some(row) = self.cursor {
+            rect_y = row as i32;
+            self.render_helper
+                .draw_rectangle(canvas, rect_y, OPCODE_CURSOR)?;
+        }
+
+        if let Some((executions, max)) = self.heat.as_ref() {
+            for (row, count) in executions
+                .iter()
+                .enumerate()
+                .filter(|(_, count)| **count > 0)
+            {
+                let color = OpcodeDisplay::heat_color(*count, *max);
+                self.heat_helper.fill_rectangle(canvas, row as i32, color)?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -106,12 +176,18 @@ impl OpcodeDisplay {
 
         OpcodeDisplay {
             code_lines: display_text,
+            code: Vec::new(),
+            listing: Listing::default(),
+            symbols: Arc::new(SymbolTable::new()),
+            entry_points: Vec::new(),
+            addresses: Vec::new(),
             memory_access: new_memory_access,
             game_properties_access: new_game_properties_access,
             debug_properties_access: new_debug_properties_access,
             states_access: new_states_access,
             offset: 0,
-            current_line: 0,
+            current_line: None,
+            scroll_address: None,
             render_helper: DisplayRenderHelper::new(
                 OPCODE_START_X,
                 OPCODE_START_Y,
@@ -119,19 +195,63 @@ impl OpcodeDisplay {
                 OPCODE_HEIGHT,
             ),
             breakpoints: HashSet::new(),
+            cursor: None,
+            heat: None,
+            heat_helper: DisplayRenderHelper::new(
+                OPCODE_START_X + (OPCODE_WIDTH - HEAT_WIDTH) as i32,
+                OPCODE_START_Y,
+                HEAT_WIDTH,
+                OPCODE_HEIGHT,
+            ),
             highlight_color: OPCODE_HIGHLIGHT_TEST,
         }
     }
 
-    fn update_offset(&self, program_size: usize) -> Option<usize> {
-        if self.current_line >= self.offset + OPCODE_LINES {
-            if self.current_line + OPCODE_LINES >= program_size {
-                return Some(program_size - OPCODE_LINES - 2);
-            } else {
-                return Some(self.current_line - OPCODE_LINES + 2);
+    fn heat_color(count: u64, max: u64) -> Color {
+        let heat = ((count as f64).ln_1p() / (max.max(1) as f64).ln_1p()).min(1.0);
+        let blend = |cold: u8, hot: u8| (cold as f64 + (hot as f64 - cold as f64) * heat) as u8;
+
+        Color::RGB(
+            blend(HEAT_COLD.r, HEAT_HOT.r),
+            blend(HEAT_COLD.g, HEAT_HOT.g),
+            blend(HEAT_COLD.b, HEAT_HOT.b),
+        )
+    }
+
+    fn update_listing(&mut self, code: Vec<u8>, symbols: Arc<SymbolTable>, program_counter: usize) {
+        let symbols_changed = !Arc::ptr_eq(&symbols, &self.symbols);
+        self.symbols = symbols;
+        if code != self.code {
+            self.code = code;
+            self.entry_points.clear();
+            self.offset = 0;
+        } else if program_counter < PROGRAM_START
+            || program_counter >= PROGRAM_START + self.code.len()
+            || self.listing.is_code(program_counter)
+            || self.entry_points.contains(&program_counter)
+        {
+            if !symbols_changed {
+                return;
             }
-        } else if self.current_line < self.offset {
-            return Some(self.current_line);
+        } else {
+            self.entry_points.push(program_counter);
+        }
+
+        self.listing = Listing::analyze(
+            &self.code,
+            &self.entry_points,
+            LISTING_DATA_BYTES,
+            &self.symbols,
+            InstructionSet::XoChip,
+        );
+    }
+
+    fn update_offset(&self) -> Option<usize> {
+        let line = self.current_line?;
+        if line >= self.offset + OPCODE_LINES / 2 {
+            return Some(line + 1 - OPCODE_LINES / 2);
+        } else if line < self.offset {
+            return Some(line);
         }
 
         None
@@ -1,21 +1,16 @@
+use crate::controller::Disassembler;
 use crate::defines::{
-    layout_constants,
-    layout_constants::{STACK_HEIGHT, STACK_HIGHLIGHT, STACK_START_X, STACK_START_Y, STACK_WIDTH},
+    layout_constants::{
+        STACK_FRAME_LINES, STACK_HEIGHT, STACK_HIGHLIGHT, STACK_START_X, STACK_START_Y, STACK_WIDTH,
+    },
     memory_constants::STACKSIZE,
-    IDisplay, ProgramState,
+    IDisplay,
 };
-use crate::model::MemoryAccess;
+use crate::model::{CpuFault, DebugPropertiesAccess, MemoryAccess};
 use crate::view::DisplayRenderHelper;
 use std::{
-    cell::RefCell,
-    rc::Rc,
     result::Result,
-    sync::{
-        mpsc::{channel, Receiver, Sender},
-        Arc, Mutex,
-    },
-    thread,
-    time::Duration,
+    sync::{Arc, Mutex},
 };
 
 use sdl2::{render::WindowCanvas, ttf::Sdl2TtfContext};
@@ -23,19 +18,46 @@ use sdl2::{render::WindowCanvas, ttf::Sdl2TtfContext};
 pub struct StackDisplay {
     stack: Vec<String>,
     memory_access: Arc<Mutex<MemoryAccess>>,
-    stack_pointer: usize,
+    debug_properties_access: Arc<Mutex<DebugPropertiesAccess>>,
+    stack_fault: Option<CpuFault>,
     render_helper: DisplayRenderHelper,
 }
 
 impl IDisplay for StackDisplay {
     fn update_info(&mut self) {
+        let symbols = self.debug_properties_access.lock().unwrap().get_symbols();
         let mut access = self.memory_access.lock().unwrap();
-        let stack = access.get_stack();
-        let stack_size = STACKSIZE - 1;
-        self.stack_pointer = access.get_stack_pointer();
+        let frames = access.get_call_frames();
+        self.stack_fault = access.get_fault().filter(CpuFault::is_stack_fault);
 
-        for (i, iter) in self.stack.iter_mut().enumerate() {
-            *iter = format!("Stack {:X}: {:04X}", stack_size - i, stack[stack_size - i]);
+        for line in self.stack.iter_mut() {
+            line.clear();
+        }
+        self.stack[0] = match self.stack_fault {
+            Some(fault) => fault.name().to_string(),
+            None => format!("Call Stack: {}", frames.len()),
+        };
+
+        let visible_frames = (STACKSIZE - 2) / STACK_FRAME_LINES;
+        for (frame, (call_site, return_address)) in frames.iter().enumerate() {
+            if frame == visible_frames {
+                self.stack[STACKSIZE - 1] = format!("+{} more", frames.len() - frame);
+                break;
+            }
+
+            let call = match access.get_memory_range(*call_site, 2) {
+                Some(bytes) => Disassembler::disassemble_with_symbols(
+                    &((bytes[0] as u16) << 8 | bytes[1] as u16),
+                    &symbols,
+                ),
+                None => String::new(),
+            };
+            let line = 1 + frame * STACK_FRAME_LINES;
+            self.stack[line] = format!("{:04X} > {:04X}", call_site, return_address);
+            self.stack[line + 1] = format!(
+                " {}",
+                call.split_whitespace().collect::<Vec<&str>>().join(" ")
+            );
         }
     }
 
@@ -47,22 +69,27 @@ impl IDisplay for StackDisplay {
         self.render_helper
             .draw_lines(&mut self.stack, canvas, ttf_context)?;
 
-        let y = STACKSIZE - self.stack_pointer - 1;
-        self.render_helper
-            .draw_rectangle(canvas, y as i32, STACK_HIGHLIGHT)?;
+        if self.stack_fault.is_some() {
+            self.render_helper
+                .draw_rectangle(canvas, 0, STACK_HIGHLIGHT)?;
+        }
 
         Ok(())
     }
 }
 
 impl StackDisplay {
-    pub fn new(new_memory_access: Arc<Mutex<MemoryAccess>>) -> StackDisplay {
+    pub fn new(
+        new_memory_access: Arc<Mutex<MemoryAccess>>,
+        new_debug_properties_access: Arc<Mutex<DebugPropertiesAccess>>,
+    ) -> StackDisplay {
         let display_text: Vec<String> = vec![String::new(); STACKSIZE];
 
         StackDisplay {
             stack: display_text,
             memory_access: new_memory_access,
-            stack_pointer: 0,
+            debug_properties_access: new_debug_properties_access,
+            stack_fault: None,
             render_helper: DisplayRenderHelper::new(
                 STACK_START_X,
                 STACK_START_Y,
@@ -1,9 +1,7 @@
-mod disassembler;
 mod display_render_helper;
 mod keypad_renderer;
 mod window_renderer;
 
-pub use self::disassembler::Disassembler;
 pub use self::display_render_helper::DisplayRenderHelper;
 pub use self::keypad_renderer::KeypadRenderer;
 pub use self::window_renderer::WindowRenderer;
@@ -1,9 +1,14 @@
 use crate::controller::{DebugManager, ProgramManager};
 use crate::defines::KeyPress;
 use crate::model::Keypad;
-use crate::sdl2::{event::Event, keyboard::Keycode, mouse::MouseButton, EventPump, Sdl};
+use crate::sdl2::{
+    event::Event,
+    keyboard::{Keycode, Mod},
+    mouse::MouseButton,
+    EventPump, Sdl,
+};
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
 };
 
@@ -13,6 +18,8 @@ pub struct InputChecker {
     program_manager: Arc<Mutex<ProgramManager>>,
     debug_manager: Arc<Mutex<DebugManager>>,
     program_keys: HashSet<Keycode>,
+    keymap: HashMap<Keycode, u8>,
+    slot_keys: HashMap<Keycode, u8>,
     //keypad_keys: HashSet<Keycode>,
 }
 
@@ -31,6 +38,10 @@ impl InputChecker {
             Keycode::F6,
             Keycode::F7,
             Keycode::F8,
+            Keycode::F9,
+            Keycode::F10,
+            Keycode::F11,
+            Keycode::F12,
             Keycode::Plus,
             Keycode::Minus,
         ]
@@ -39,12 +50,50 @@ impl InputChecker {
 
         let _new_keypad_keys: HashSet<Keycode> = vec![].into_iter().collect();
 
+        let new_keymap: HashMap<Keycode, u8> = vec![
+            (Keycode::Num1, 0x1),
+            (Keycode::Num2, 0x2),
+            (Keycode::Num3, 0x3),
+            (Keycode::Num4, 0xC),
+            (Keycode::Q, 0x4),
+            (Keycode::W, 0x5),
+            (Keycode::E, 0x6),
+            (Keycode::R, 0xD),
+            (Keycode::A, 0x7),
+            (Keycode::S, 0x8),
+            (Keycode::D, 0x9),
+            (Keycode::F, 0xE),
+            (Keycode::Y, 0xA),
+            (Keycode::X, 0x0),
+            (Keycode::C, 0xB),
+            (Keycode::V, 0xF),
+        ]
+        .into_iter()
+        .collect();
+
+        let new_slot_keys: HashMap<Keycode, u8> = vec![
+            (Keycode::Num0, 0),
+            (Keycode::Num1, 1),
+            (Keycode::Num2, 2),
+            (Keycode::Num3, 3),
+            (Keycode::Num4, 4),
+            (Keycode::Num5, 5),
+            (Keycode::Num6, 6),
+            (Keycode::Num7, 7),
+            (Keycode::Num8, 8),
+            (Keycode::Num9, 9),
+        ]
+        .into_iter()
+        .collect();
+
         InputChecker {
             event_pump: sdl_context.event_pump().unwrap(),
             keypad: new_keypad,
             program_manager: new_program_manager,
             debug_manager: new_debug_manager,
             program_keys: new_program_keys,
+            keymap: new_keymap,
+            slot_keys: new_slot_keys,
             //keypad_keys:
         }
     }
@@ -58,7 +107,9 @@ impl InputChecker {
 
         for event in events.iter() {
             match event {
-                Event::KeyDown { keycode, .. } => self.process_keydown(keycode.unwrap()),
+                Event::KeyDown {
+                    keycode, keymod, ..
+                } => self.process_keydown(keycode.unwrap(), *keymod),
                 Event::KeyUp { keycode, .. } => self.process_keyup(keycode.unwrap()),
                 Event::DropFile { filename, .. } => {
                     self.program_manager.lock().unwrap().new_file(filename)
@@ -72,7 +123,32 @@ impl InputChecker {
         }
     }
 
-    fn process_keydown(&mut self, key: Keycode) {
+    fn process_keydown(&mut self, key: Keycode, keymod: Mod) {
+        {
+            let mut debug_manager = self.debug_manager.lock().unwrap();
+            if debug_manager.is_editing_register() {
+                debug_manager.edit_register(key);
+                return;
+            }
+        }
+
+        if let Some(slot) = self.slot_keys.get(&key) {
+            if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) {
+                self.program_manager
+                    .lock()
+                    .unwrap()
+                    .request_save_state(*slot);
+                return;
+            }
+            if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+                self.program_manager
+                    .lock()
+                    .unwrap()
+                    .request_load_state(*slot);
+                return;
+            }
+        }
+
         let mut keypad_ref = self.keypad.lock().unwrap();
         match key {
             Keycode::F1
@@ -80,34 +156,61 @@ impl InputChecker {
             | Keycode::F4
             | Keycode::Plus
             | Keycode::Minus
-            | Keycode::L => self.program_manager.lock().unwrap().press_key(key),
-            Keycode::F6 | Keycode::F3 | Keycode::F7 | Keycode::F8 => {
-                self.debug_manager.lock().unwrap().press_key(key)
-            }
+            | Keycode::L
+            | Keycode::P
+            | Keycode::T
+            | Keycode::Tab => self.program_manager.lock().unwrap().press_key(key),
+            Keycode::F6
+            | Keycode::F3
+            | Keycode::F7
+            | Keycode::F8
+            | Keycode::F9
+            | Keycode::F10
+            | Keycode::F11
+            | Keycode::F12
+            | Keycode::H
+            | Keycode::J => self.debug_manager.lock().unwrap().press_key(key),
             Keycode::F5 => {
                 self.program_manager.lock().unwrap().press_key(key);
             }
             Keycode::K => {
                 keypad_ref.toggle_key_reset();
             }
+            Keycode::Backspace => self.program_manager.lock().unwrap().start_rewind(),
 
-            _ => keypad_ref.press_key(key, KeyPress::Down),
+            _ => {
+                if let Some(keypad_key) = self.keymap.get(&key) {
+                    keypad_ref.set_key(*keypad_key, KeyPress::Down);
+                }
+            }
         }
     }
 
     fn process_keyup(&mut self, key: Keycode) {
+        if key == Keycode::Backspace {
+            self.program_manager.lock().unwrap().stop_rewind();
+        }
+
         let mut keypad_ref = self.keypad.lock().unwrap();
-        match key {
-            _ => keypad_ref.press_key(key, KeyPress::Up),
+        if let Some(keypad_key) = self.keymap.get(&key) {
+            keypad_ref.set_key(*keypad_key, KeyPress::Up);
         }
     }
 
     fn process_mouse_click(&mut self, button: &MouseButton, x: &i32, y: &i32) {
-        if *button == MouseButton::Left {
-            self.debug_manager
+        match *button {
+            MouseButton::Left => {
+                let mut debug_manager = self.debug_manager.lock().unwrap();
+                debug_manager.set_breakpoint_on_mouse_click(x, y);
+                debug_manager.select_register_on_mouse_click(x, y);
+                debug_manager.select_stack_frame_on_mouse_click(x, y);
+            }
+            MouseButton::Right => self
+                .debug_manager
                 .lock()
                 .unwrap()
-                .set_breakpoint_on_mouse_click(x, y);
+                .set_cursor_on_mouse_click(x, y),
+            _ => {}
         }
     }
 }
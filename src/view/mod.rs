@@ -1,3 +1,4 @@
+mod console;
 mod display_manager;
 mod displays;
 mod helper;
@@ -6,6 +7,7 @@ mod sound_manager;
 mod view;
 mod view_data;
 
+pub use self::console::DebugConsole;
 pub use self::display_manager::*;
 pub use self::displays::*;
 pub use self::helper::*;
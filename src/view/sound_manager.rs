@@ -1,12 +1,28 @@
 use crate::controller::TimeTo;
+use crate::defines::memory_constants::{AUDIO_PATTERN_SIZE, BASE_PITCH};
+use crate::model::MemoryAccess;
 use sdl2::audio::{AudioCallback, AudioDevice, AudioSpecDesired};
 use sdl2::Sdl;
-use std::sync::{mpsc::Receiver, Arc};
+use std::sync::{mpsc::Receiver, Arc, Mutex};
+
+pub const PATTERN_BASE_RATE: f32 = 4000.0;
+pub const PATTERN_BITS: usize = AUDIO_PATTERN_SIZE * 8;
 
 struct SquareWave {
     phase_inc: f32,
     phase: f32,
     volume: f32,
+    frequency: f32,
+    pattern: Option<[u8; AUDIO_PATTERN_SIZE]>,
+    pattern_inc: f32,
+}
+
+impl SquareWave {
+    fn update_pattern(&mut self, pattern: Option<[u8; AUDIO_PATTERN_SIZE]>, pitch: u8) {
+        let rate = PATTERN_BASE_RATE * 2f32.powf((pitch as f32 - BASE_PITCH as f32) / 48.0);
+        self.pattern_inc = rate / PATTERN_BITS as f32 / self.frequency;
+        self.pattern = pattern;
+    }
 }
 
 impl AudioCallback for SquareWave {
@@ -14,12 +30,22 @@ impl AudioCallback for SquareWave {
 
     fn callback(&mut self, out: &mut [f32]) {
         for x in out.iter_mut() {
-            *x = if self.phase <= 0.5 {
-                self.volume
+            if let Some(pattern) = self.pattern {
+                let bit = (self.phase * PATTERN_BITS as f32) as usize % PATTERN_BITS;
+                *x = if pattern[bit / 8] & (0x80 >> (bit % 8)) != 0 {
+                    self.volume
+                } else {
+                    -self.volume
+                };
+                self.phase = (self.phase + self.pattern_inc) % 1.0;
             } else {
-                -self.volume
-            };
-            self.phase = (self.phase + self.phase_inc) % 1.0;
+                *x = if self.phase <= 0.5 {
+                    self.volume
+                } else {
+                    -self.volume
+                };
+                self.phase = (self.phase + self.phase_inc) % 1.0;
+            }
         }
     }
 }
@@ -27,10 +53,15 @@ impl AudioCallback for SquareWave {
 pub struct SoundManager {
     audio_device: AudioDevice<SquareWave>,
     audio_receiver: Receiver<TimeTo>,
+    memory_access: Arc<Mutex<MemoryAccess>>,
 }
 
 impl SoundManager {
-    pub fn new(context: Arc<Sdl>, receiver: Receiver<TimeTo>) -> SoundManager {
+    pub fn new(
+        context: Arc<Sdl>,
+        receiver: Receiver<TimeTo>,
+        new_memory_access: Arc<Mutex<MemoryAccess>>,
+    ) -> SoundManager {
         let subsystem = context.audio().unwrap();
         let desired_spec = AudioSpecDesired {
             freq: Some(44100),
@@ -43,12 +74,16 @@ impl SoundManager {
                 phase_inc: 240.0 / spec.freq as f32,
                 phase: 0.0,
                 volume: 0.25,
+                frequency: spec.freq as f32,
+                pattern: None,
+                pattern_inc: 0.0,
             })
             .unwrap();
 
         SoundManager {
             audio_device: device,
             audio_receiver: receiver,
+            memory_access: new_memory_access,
         }
     }
 
@@ -63,6 +98,10 @@ impl SoundManager {
     }
 
     pub fn play_sound(&mut self) {
+        let mut access = self.memory_access.lock().unwrap();
+        let pattern = access.get_audio_pattern();
+        let pitch = access.get_pitch();
+        self.audio_device.lock().update_pattern(pattern, pitch);
         self.audio_device.resume();
     }
 
@@ -29,7 +29,11 @@ impl View {
                 let keypad_copy = Arc::clone(&new_keypad);
                 let sdl_context = sdl2::init().unwrap();
                 let context = Arc::new(sdl_context);
-                let sound_manager = SoundManager::new(Arc::clone(&context), audio_receiver);
+                let sound_manager = SoundManager::new(
+                    Arc::clone(&context),
+                    audio_receiver,
+                    Arc::clone(&memory_access),
+                );
                 let input_checker = InputChecker::new(
                     Arc::clone(&context),
                     Arc::clone(&keypad_copy),